@@ -14,12 +14,14 @@ class Dashboard:
         self.layout.split(
             Layout(name="header", size=3),
             Layout(name="body", ratio=1),
+            Layout(name="providers", size=8),
             Layout(name="footer", size=3)
         )
         self.logs = []
         self.max_logs = 10
         self.roi_current = 0.0
         self.roi_target = 15.0 # $15.00
+        self.providers = []
 
     def generate_header(self, block, gas_gwei, eth_balance=0.0):
         grid = Table.grid(expand=True)
@@ -45,6 +47,28 @@ class Dashboard:
             
         return Panel(table, title="Strategy Execution", border_style="green")
 
+    def generate_providers(self):
+        table = Table(expand=True, box=box.SIMPLE)
+        table.add_column("Endpoint")
+        table.add_column("State", width=12)
+        table.add_column("Latency", justify="right", width=9)
+        table.add_column("Errors", justify="right", width=7)
+        table.add_column("Block", justify="right", width=9)
+        table.add_column("Last Error", style="dim", ratio=1, no_wrap=True)
+
+        for p in self.providers:
+            if p.chain_id_ok is False:
+                state = "[red]WRONG CHAIN[/red]"
+            elif not p.healthy:
+                state = f"[yellow]COOL {p.cooldown_secs:.0f}s[/yellow]"
+            else:
+                state = "[green]OK[/green]"
+            latency = f"{p.latency_ms:.0f} ms" if p.latency_ms is not None else "-"
+            block = str(p.last_block) if p.last_block is not None else "-"
+            table.add_row(p.endpoint, state, latency, f"{p.error_rate:.0%}", block, p.last_error or "")
+
+        return Panel(table, title="RPC Providers", border_style="cyan")
+
     def generate_footer(self):
         progress = (self.roi_current / self.roi_target) * 100
         return Panel(
@@ -61,9 +85,14 @@ class Dashboard:
         usd_value = profit_sway / 1000.0 
         self.roi_current += usd_value
 
+    def update_providers(self, health):
+        # Rows from PyStarknetClient.get_provider_health()
+        self.providers = list(health)
+
     def render(self, block, gas_gwei, eth_balance=0.0):
         self.layout["header"].update(self.generate_header(block, gas_gwei, eth_balance))
         self.layout["body"].update(self.generate_body())
+        self.layout["providers"].update(self.generate_providers())
         self.layout["footer"].update(self.generate_footer())
         return self.layout
 
@@ -56,13 +56,82 @@ def init():
     if not RUST_AVAILABLE:
         console.print("[bold red]Critical Error: Rust extension not found.[/bold red]")
         return
-    password = Prompt.ask("Create A Vault Password", password=True)
+    vault_path = os.getenv("VAULT_PATH", "vault.bin")
+    if os.path.exists(vault_path):
+        password = Prompt.ask("Vault Password", password=True)
+    else:
+        password = Prompt.ask("Create A Vault Password", password=True)
+        if password != Prompt.ask("Confirm Vault Password", password=True):
+            console.print("[red]Passwords do not match. No vault created.[/red]")
+            return
     try:
-        vault = stark_pyrust_chain.PyVault(password)
-        console.print("[green]Vault initialized successfully.[/green]")
+        vault = stark_pyrust_chain.PyVault.open(vault_path, password)
+        console.print(f"[green]Vault initialized successfully ({vault_path}).[/green]")
+        if Confirm.ask("Store your Starknet private key in the vault now?"):
+            private_key = Prompt.ask("Starknet Private Key", password=True)
+            vault.set_secret("STARKNET_PRIVATE_KEY", private_key)
+            console.print("[green]STARKNET_PRIVATE_KEY sealed in vault.[/green]")
     except Exception as e:
         console.print(f"[red]Initialization failed: {e}[/red]")
 
+@app.command()
+def rekey():
+    """ Rotate the vault password and re-encrypt every stored secret. """
+    if not RUST_AVAILABLE:
+        console.print("[bold red]Critical Error: Rust extension not found.[/bold red]")
+        return
+    vault_path = os.getenv("VAULT_PATH", "vault.bin")
+    if not os.path.exists(vault_path):
+        console.print(f"[red]No vault found at {vault_path}. Run 'init' first.[/red]")
+        return
+    old_password = Prompt.ask("Current Vault Password", password=True)
+    new_password = Prompt.ask("New Vault Password", password=True)
+    if new_password != Prompt.ask("Confirm New Vault Password", password=True):
+        console.print("[red]Passwords do not match. Vault unchanged.[/red]")
+        return
+    try:
+        vault = stark_pyrust_chain.PyVault.open(vault_path, old_password)
+        vault.rekey(old_password, new_password)
+        console.print("[green]Vault re-keyed. The old password no longer opens it.[/green]")
+        console.print("[dim]Re-run export-blob and update STARK_VAULT_BLOB / STARK_VAULT_PASSWORD; the old blob still opens with the old password.[/dim]")
+    except Exception as e:
+        console.print(f"[red]Re-key failed (vault unchanged): {e}[/red]")
+
+@app.command()
+def export_blob():
+    """ Print the encrypted vault as base64 for the STARK_VAULT_BLOB GitHub Secret. """
+    if not RUST_AVAILABLE:
+        console.print("[bold red]Critical Error: Rust extension not found.[/bold red]")
+        return
+    vault_path = os.getenv("VAULT_PATH", "vault.bin")
+    if not os.path.exists(vault_path):
+        console.print(f"[red]No vault found at {vault_path}. Run 'init' first.[/red]")
+        return
+    password = Prompt.ask("Vault Password", password=True)
+    try:
+        vault = stark_pyrust_chain.PyVault.open(vault_path, password)
+        print(vault.export_blob())
+        console.print("[dim]Store this as STARK_VAULT_BLOB and the password as STARK_VAULT_PASSWORD.[/dim]")
+    except Exception as e:
+        console.print(f"[red]Export failed: {e}[/red]")
+
+@app.command()
+def revoke_session(public_key: str = typer.Argument(None), reason: str = "manual revocation"):
+    """ Revoke a session key so no signing path will use it again; lists revocations when no key is given. """
+    if not RUST_AVAILABLE:
+        console.print("[bold red]Critical Error: Rust extension not found.[/bold red]")
+        return
+    try:
+        registry = stark_pyrust_chain.PyRevocationRegistry()
+        if public_key:
+            registry.revoke(public_key, reason)
+            console.print(f"[green]Session key {public_key[:10]}... revoked.[/green]")
+        for key, why, revoked_at in registry.list():
+            console.print(f"[dim]{time.strftime('%Y-%m-%d %H:%M', time.gmtime(revoked_at))}[/dim] {key} ({why})")
+        console.print(f"[dim]On-chain calldata: {' '.join(registry.revocation_calldata())}[/dim]")
+    except Exception as e:
+        console.print(f"[red]Revocation failed: {e}[/red]")
+
 @app.command()
 def wizard():
     """ The 'Introduction Wizard' for Starknet & Influence setup. """
@@ -127,9 +196,11 @@ def start(strategy: str = "refine", dry_run: bool = True):
                         wei_bal = active_strategy.starknet.get_eth_balance(wallet_addr)
                         eth_balance = wei_bal / 1e18
 
+                    dash.update_providers(active_strategy.starknet.get_provider_health())
                     live.update(dash.render(block, gas_gwei, eth_balance))
                 except Exception as e:
                     # dash.log(f"Status Error: {e}") # Debug only
+                    dash.update_providers(active_strategy.starknet.get_provider_health())
                     live.update(dash.render(0, 0, 0.0))
 
                 # 2. Run Strategy Tick
@@ -138,6 +209,7 @@ def start(strategy: str = "refine", dry_run: bool = True):
                 # 3. Sleep (responsive update)
                 for _ in range(60): # 60 seconds sleep, updating UI every 1s
                     time.sleep(1)
+                    dash.update_providers(active_strategy.starknet.get_provider_health())
                     live.update(dash.render(block, gas_gwei, eth_balance))
                     
         except KeyboardInterrupt:
@@ -163,7 +235,7 @@ def pulse(strategy: str = "refine", dry_run: bool = True):
         # Simple logging for Pulse (Stdout) with Masking
         def pulse_log(msg):
             # 1. Mask known secrets if they somehow leak into logs
-            secrets = [os.getenv("STARKNET_PRIVATE_KEY"), os.getenv("INFLUENCE_API_KEY")]
+            secrets = [os.getenv("STARK_VAULT_PASSWORD"), os.getenv("INFLUENCE_API_KEY")]
             for s in secrets:
                 if s and s in msg:
                     msg = msg.replace(s, "***SECRET***")
@@ -184,12 +256,12 @@ def pulse(strategy: str = "refine", dry_run: bool = True):
     
     # Secret Presence Check (Masked)
     rpc = os.getenv("STARKNET_RPC_URL")
-    pk = os.getenv("STARKNET_PRIVATE_KEY")
+    vault_blob = os.getenv("STARK_VAULT_BLOB")
     inf_key = os.getenv("INFLUENCE_API_KEY")
     
     print(f"Secret Check:")
     print(f"  RPC_URL: {'[CONFIGURED]' if rpc else '[MISSING]'} {f'({rpc[:8]}...)' if rpc else ''}")
-    print(f"  PRIVATE_KEY: {'[CONFIGURED]' if pk else '[MISSING]'}")
+    print(f"  VAULT_BLOB: {'[CONFIGURED]' if vault_blob else '[MISSING]'}")
     print(f"  INFLUENCE_KEY: {'[CONFIGURED]' if inf_key else '[MISSING]'}")
 
     # 1. Update Network Status (Log only)
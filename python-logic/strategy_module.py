@@ -1,3 +1,4 @@
+import os
 import time
 import json
 import logging
@@ -26,28 +27,100 @@ class BaseStrategy:
         logging.info(message)
         console.print(f"[dim]{message}[/dim]")
 
+    def open_vault(self):
+        """
+        Open the encrypted vault: the CI blob (STARK_VAULT_BLOB, read-only) when present,
+        otherwise the local file (VAULT_PATH, default vault.bin).
+        Returns None if the vault is missing or locked.
+        """
+        try:
+            if os.getenv("STARK_VAULT_BLOB"):
+                return stark_pyrust_chain.PyVault.from_env()
+            vault_path = os.getenv("VAULT_PATH", "vault.bin")
+            password = os.getenv("VAULT_PASSWORD")
+            if not password or not os.path.exists(vault_path):
+                return None
+            return stark_pyrust_chain.PyVault.open(vault_path, password)
+        except Exception as e:
+            self.log(f"Warning: Vault unavailable ({e}).")
+            return None
+
+    def load_vault_secret(self, name):
+        """
+        Read a named secret from self.vault. The vault is opened at most once per
+        strategy, so a start costs one key derivation and a wrong password counts once
+        against the unlock backoff. Returns a redacted PySecret (call .expose() at the
+        point of use), or None if unavailable.
+        """
+        if not hasattr(self, "vault"):
+            self.vault = self.open_vault()
+        if self.vault is None:
+            return None
+        try:
+            return self.vault.get_secret(name)
+        except Exception as e:
+            self.log(f"Warning: Vault secret {name} unreadable ({e}).")
+            return None
+
 class RefiningStrategy(BaseStrategy):
     """
     Automates the Iron -> Steel refining loop.
     """
+    SESSION_KEY_ENTRY = "SESSION_KEY"
+    SESSION_LIFETIME_SECS = 24 * 3600
+    SESSION_ROTATE_MARGIN_SECS = 3600
+
     def __init__(self, dry_run=True):
         super().__init__(dry_run)
         self.client = stark_pyrust_chain.PyInfluenceClient()
         self.graph = stark_pyrust_chain.PySupplyChain()
         # Explicitly pass URL from Env to avoid Rust-side dot-env issues
-        import os
         rpc_url = os.getenv("STARKNET_MAINNET_URL") or os.getenv("STARKNET_RPC_URL")
         self.starknet = stark_pyrust_chain.PyStarknetClient(rpc_url)
         self.influence = stark_pyrust_chain.PyInfluenceClient() # New for ADR-041
-        
-        # Load or generate Session Key (In real app, load from Vault)
-        # For v0.1.0 demo, we generate a fresh one or assume it's loaded
+
+        # Account key lives in the encrypted vault, never in .env
+        self.vault = self.open_vault()
+        self.account_key = self.load_vault_secret("STARKNET_PRIVATE_KEY")
+        if self.account_key is None:
+            self.log("Warning: STARKNET_PRIVATE_KEY not found in vault. Only read-ops available.")
+
+        self.session_authorization = None
+        self.session_key = self.load_session_key()
+
+    def load_session_key(self):
+        """
+        Load the session key persisted in the vault, rotating it when it is missing or
+        within SESSION_ROTATE_MARGIN_SECS of expiry. A rotated key's SNIP-12
+        authorization is signed with the vault's master key.
+        Falls back to an unpersisted key when the vault is read-only or unavailable.
+        """
+        master_account = os.getenv("STARKNET_ACCOUNT_ADDRESS")
+        if self.vault is not None and master_account:
+            try:
+                key, authorization = stark_pyrust_chain.PySessionKey.rotate_in_vault(
+                    self.vault, self.SESSION_KEY_ENTRY, master_account,
+                    self.SESSION_LIFETIME_SECS, self.SESSION_ROTATE_MARGIN_SECS)
+                if authorization is None:
+                    self.log("Session Key loaded from vault.")
+                else:
+                    typed_data, auth_hash = authorization
+                    self.log(f"Session Key rotated (Pub: {key.get_public_key()[:10]}...).")
+                    if self.account_key is not None:
+                        signature = self.vault.sign_hash("STARKNET_PRIVATE_KEY", auth_hash)
+                        self.session_authorization = (typed_data, auth_hash, signature)
+                        self.log(f"Authorization {auth_hash[:10]}... signed by master account.")
+                return key
+            except Exception as e:
+                self.log(f"Warning: Session Key not persisted ({e}).")
+
         try:
-            self.session_key = stark_pyrust_chain.PySessionKey()
-            self.log("Session Key loaded.")
+            key = stark_pyrust_chain.PySessionKey()
+            self.log("Ephemeral Session Key generated.")
+            return key
         except Exception as e:
             self.log(f"Warning: Session Key missing ({e}). Only read-ops available.")
-            self.session_key = None
+            return None
 
     def tick(self):
         """
@@ -0,0 +1,164 @@
+use anyhow::{Context, Result};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use crate::state_file::unix_now;
+
+/// `prev_hash` of the first record in a log.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+/// How far back from EOF to look for the last record before falling back to a full read.
+const TAIL_WINDOW: u64 = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOp {
+    Unlock,
+    UnlockFailed,
+    Read,
+    Write,
+    Delete,
+    Rekey,
+    ExportBlob,
+    DecryptFailed,
+}
+
+/// One line of the audit log. Only secret *names* are ever recorded.
+/// `hash = blake3(json([seq, timestamp, op, secret, prev_hash]))`, chaining each record
+/// to the one before it so edits, deletions and reordering are detectable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub seq: u64,
+    pub timestamp: u64,
+    pub op: AuditOp,
+    pub secret: Option<String>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditRecord {
+    fn digest(seq: u64, timestamp: u64, op: AuditOp, secret: &Option<String>, prev_hash: &str) -> Result<String> {
+        let canonical = serde_json::to_vec(&(seq, timestamp, op, secret, prev_hash))?;
+        Ok(blake3::hash(&canonical).to_hex().to_string())
+    }
+}
+
+/// Result of [`verify_chain`]. `first_broken` is the 1-based line of the first bad record.
+#[derive(Debug, Clone)]
+pub struct AuditReport {
+    pub records: u64,
+    pub first_broken: Option<u64>,
+    pub reason: Option<String>,
+}
+
+/// Append-only, hash-chained log of vault access (`<vault>.audit`).
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        AuditLog { path: path.as_ref().to_path_buf() }
+    }
+
+    /// Append a record, holding an exclusive lock on the log while reading the chain head.
+    pub fn append(&self, op: AuditOp, secret: Option<&str>) -> Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)
+            .context(format!("Failed to open audit log: {}", self.path.display()))?;
+        file.lock_exclusive().context("Failed to lock audit log")?;
+        let result = Self::append_locked(&mut file, op, secret);
+        let _ = FileExt::unlock(&file);
+        result
+    }
+
+    fn append_locked(file: &mut File, op: AuditOp, secret: Option<&str>) -> Result<()> {
+        let (seq, prev_hash) = match last_record(file)? {
+            Some(last) => (last.seq + 1, last.hash),
+            None => (0, GENESIS_HASH.to_string()),
+        };
+        let timestamp = unix_now()?;
+        let secret = secret.map(str::to_string);
+        let hash = AuditRecord::digest(seq, timestamp, op, &secret, &prev_hash)?;
+
+        let record = AuditRecord { seq, timestamp, op, secret, prev_hash, hash };
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        file.write_all(&line)?;
+        file.sync_data()?;
+        Ok(())
+    }
+}
+
+/// Last complete record in the log, reading only the tail when possible.
+fn last_record(file: &mut File) -> Result<Option<AuditRecord>> {
+    let len = file.metadata()?.len();
+    if len == 0 {
+        return Ok(None);
+    }
+
+    let start = len.saturating_sub(TAIL_WINDOW);
+    file.seek(SeekFrom::Start(start))?;
+    let mut raw = Vec::new();
+    file.read_to_end(&mut raw)?;
+    let mut tail = String::from_utf8_lossy(&raw).into_owned();
+
+    // The window may start mid-record; if it holds no complete line, read everything.
+    if start > 0 && tail.lines().filter(|l| !l.trim().is_empty()).count() < 2 {
+        file.seek(SeekFrom::Start(0))?;
+        tail.clear();
+        file.read_to_string(&mut tail).context("Audit log is not valid UTF-8")?;
+    }
+
+    let last_line = tail.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or_default();
+    let record = serde_json::from_str(last_line)
+        .context("Audit log tail is corrupt; run verify_chain")?;
+    Ok(Some(record))
+}
+
+/// Walk the whole log and check every hash link.
+pub fn verify_chain<P: AsRef<Path>>(path: P) -> Result<AuditReport> {
+    let file = File::open(path.as_ref())
+        .context(format!("Failed to open audit log: {}", path.as_ref().display()))?;
+
+    let mut expected_prev = GENESIS_HASH.to_string();
+    let mut expected_seq = 0u64;
+    let mut records = 0u64;
+    let broken = |line_no: u64, records: u64, reason: String| AuditReport {
+        records,
+        first_broken: Some(line_no),
+        reason: Some(reason),
+    };
+
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line_no = i as u64 + 1;
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: AuditRecord = match serde_json::from_str(&line) {
+            Ok(r) => r,
+            Err(e) => return Ok(broken(line_no, records, format!("unparseable record: {}", e))),
+        };
+        if record.seq != expected_seq {
+            return Ok(broken(line_no, records, format!("sequence {} where {} was expected", record.seq, expected_seq)));
+        }
+        if record.prev_hash != expected_prev {
+            return Ok(broken(line_no, records, "prev_hash does not match the preceding record".to_string()));
+        }
+        let digest = AuditRecord::digest(record.seq, record.timestamp, record.op, &record.secret, &record.prev_hash)?;
+        if digest != record.hash {
+            return Ok(broken(line_no, records, "record contents do not match its hash".to_string()));
+        }
+
+        expected_prev = record.hash;
+        expected_seq += 1;
+        records += 1;
+    }
+
+    Ok(AuditReport { records, first_broken: None, reason: None })
+}
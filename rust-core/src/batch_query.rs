@@ -0,0 +1,37 @@
+use starknet::core::types::FieldElement;
+
+use crate::tokens::TokenBalance;
+
+/// Address of Influence's asteroid ERC-721 contract on the connected network.
+pub const ASTEROID_CONTRACT_ENV: &str = "INFLUENCE_ASTEROID_ADDRESS";
+/// Calls per JSON-RPC batch; providers reject or truncate much larger ones.
+pub const MAX_BATCH_CALLS: usize = 50;
+
+/// Who owns one asteroid. This is all `batch_query` reads about asteroids so far:
+/// lot-level state (buildings, deposits, inventories) lives behind Influence's
+/// dispatcher contract, and reading it stays open until its ABI is pinned here.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsteroidOwnership {
+    pub asteroid_id: u64,
+    /// `ownerOf(asteroid_id)`; `None` when the read failed (e.g. not minted).
+    pub owner: Option<FieldElement>,
+    /// Why `owner` is missing.
+    pub error: Option<String>,
+}
+
+impl AsteroidOwnership {
+    pub fn owned_by(&self, account: FieldElement) -> bool {
+        self.owner == Some(account)
+    }
+}
+
+/// Everything `batch_query` read for one account, at one block.
+#[derive(Debug, Clone)]
+pub struct BatchQueryResult {
+    pub account: FieldElement,
+    pub block_number: u64,
+    pub sway: TokenBalance,
+    pub asteroid_owners: Vec<AsteroidOwnership>,
+    /// Whether the provider took JSON-RPC batches or the calls were sent one by one.
+    pub batched: bool,
+}
@@ -0,0 +1,169 @@
+use aes_gcm::aead::OsRng;
+use anyhow::{Context, Result};
+use bip32::{DerivationPath, Language, Mnemonic, XPrv};
+use crate::session_keys::{SessionKey, EC_ORDER};
+use crypto_bigint::{Encoding, NonZero, U256};
+use sha2::{Digest, Sha256};
+use starknet::core::types::FieldElement;
+use starknet::signers::SigningKey;
+use zeroize::Zeroizing;
+
+/// `m/44'/9004'/0'/0/<index>`: the account path Braavos derives from the mnemonic.
+/// Argent X is not compatible: it first derives an Ethereum key at `m/44'/60'/0'/0/0`
+/// and uses that as the seed for its Stark keys.
+pub const ACCOUNT_PATH_PREFIX: &str = "m/44'/9004'/0'/0";
+/// EIP-2645 layer and application used for this bot's session keys.
+const SESSION_LAYER: &str = "starknet";
+const SESSION_APPLICATION: &str = "stark_pyrust_chain.session";
+
+/// BIP-32 master seed from which every Stark key of the bot can be regenerated.
+pub struct HdWallet {
+    seed: Zeroizing<Vec<u8>>,
+}
+
+impl HdWallet {
+    /// New random 24-word English mnemonic.
+    pub fn generate_mnemonic() -> Zeroizing<String> {
+        Zeroizing::new(Mnemonic::random(OsRng, Language::English).phrase().to_string())
+    }
+
+    pub fn from_mnemonic(phrase: &str, passphrase: &str) -> Result<Self> {
+        let mnemonic = Mnemonic::new(phrase.trim(), Language::English)
+            .map_err(|_| anyhow::anyhow!("Invalid BIP-39 mnemonic"))?;
+        let seed = mnemonic.to_seed(passphrase);
+        Ok(HdWallet { seed: Zeroizing::new(seed.as_bytes().to_vec()) })
+    }
+
+    /// Raw BIP-32 seed (16 to 64 bytes).
+    pub fn from_seed(seed: &[u8]) -> Result<Self> {
+        if !(16..=64).contains(&seed.len()) {
+            return Err(anyhow::anyhow!("Seed must be 16 to 64 bytes, got {}", seed.len()));
+        }
+        Ok(HdWallet { seed: Zeroizing::new(seed.to_vec()) })
+    }
+
+    /// Stark private key at a BIP-32 `path`: secp256k1 derivation, then the StarkEx
+    /// `grindKey` step that maps the child key uniformly onto the STARK curve order.
+    pub fn derive_private_key(&self, path: &str) -> Result<FieldElement> {
+        let path: DerivationPath = path.parse()
+            .map_err(|_| anyhow::anyhow!("Invalid derivation path: {}", path))?;
+        let child = XPrv::derive_from_path(self.seed.as_slice(), &path)
+            .map_err(|e| anyhow::anyhow!("Key derivation failed: {}", e))?;
+        let child_bytes = Zeroizing::new(child.to_bytes());
+        grind_key(child_bytes.as_slice())
+    }
+
+    pub fn derive_public_key(&self, path: &str) -> Result<FieldElement> {
+        let private_key = self.derive_private_key(path)?;
+        Ok(SigningKey::from_secret_scalar(private_key).verifying_key().scalar())
+    }
+
+    /// Account key `index`, matching what Braavos derives from the same mnemonic.
+    pub fn account_private_key(&self, index: u32) -> Result<FieldElement> {
+        self.derive_private_key(&format!("{}/{}", ACCOUNT_PATH_PREFIX, index))
+    }
+
+    /// Session key `index` on the bot's own EIP-2645 path, so keys can be regenerated
+    /// instead of backed up one by one.
+    pub fn session_key(&self, index: u32) -> Result<SessionKey> {
+        let path = eip2645_path(SESSION_LAYER, SESSION_APPLICATION, "0x0", index)?;
+        SessionKey::from_private_key(self.derive_private_key(&path)?)
+    }
+}
+
+/// `m/2645'/layer'/application'/eth_address_1'/eth_address_2'/index`, where layer and
+/// application are the low 31 bits of their SHA-256 and the Ethereum address is split
+/// into its low two 31-bit chunks.
+pub fn eip2645_path(layer: &str, application: &str, eth_address: &str, index: u32) -> Result<String> {
+    let address = FieldElement::from_hex_be(eth_address.trim())
+        .map_err(|_| anyhow::anyhow!("Invalid Ethereum address: {}", eth_address))?
+        .to_bytes_be();
+    if address[..12].iter().any(|b| *b != 0) {
+        return Err(anyhow::anyhow!("Ethereum address must be 20 bytes: {}", eth_address));
+    }
+
+    let address_low = u64::from_be_bytes(address[24..].try_into()?);
+    Ok(format!(
+        "m/2645'/{}'/{}'/{}'/{}'/{}",
+        low_31_bits(&Sha256::digest(layer.as_bytes())),
+        low_31_bits(&Sha256::digest(application.as_bytes())),
+        address_low & 0x7fff_ffff,
+        (address_low >> 31) & 0x7fff_ffff,
+        index
+    ))
+}
+
+fn low_31_bits(bytes: &[u8]) -> u32 {
+    let tail: [u8; 4] = bytes[bytes.len() - 4..].try_into().unwrap_or_default();
+    u32::from_be_bytes(tail) & 0x7fff_ffff
+}
+
+/// StarkEx `grindKey`: hash `seed || i` with SHA-256 until the digest falls below the
+/// largest multiple of the curve order that fits in 256 bits, then reduce it.
+fn grind_key(seed: &[u8]) -> Result<FieldElement> {
+    let order = NonZero::from_uint(U256::from_be_slice(&EC_ORDER));
+    // 2^256 - (2^256 mod order); the order is odd, so the remainder is never zero.
+    let remainder = U256::MAX.rem(&order).wrapping_add(&U256::ONE).rem(&order);
+    let limit = U256::MAX.wrapping_sub(&remainder).wrapping_add(&U256::ONE);
+
+    let mut i = 0u64;
+    loop {
+        let index_bytes = i.to_be_bytes();
+        let first = index_bytes.iter().position(|b| *b != 0).unwrap_or(7);
+        let digest = Sha256::new()
+            .chain_update(seed)
+            .chain_update(&index_bytes[first..])
+            .finalize();
+        let candidate = U256::from_be_slice(&digest);
+        if candidate < limit {
+            let key = candidate.rem(&order).to_be_bytes();
+            return FieldElement::from_bytes_be(&key).context("Ground key out of range");
+        }
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Vectors from the StarkEx `key_derivation` tests (starkware-libs/starkex-resources)
+    // and the EIP-2645 example.
+    const MNEMONIC: &str = "range mountain blast problem vibrant void vivid doctor cluster enough melody salt layer \
+                            language laptop boat major space monkey unit glimpse pause change vibrant";
+    const ETH_ADDRESS: &str = "0xa4864d977b944315389d1765ffa7e66F74ee8cd7";
+
+    fn felt(hex: &str) -> FieldElement {
+        FieldElement::from_hex_be(hex).unwrap()
+    }
+
+    #[test]
+    fn eip2645_path_matches_spec() {
+        assert_eq!(
+            eip2645_path("starkex", "starkdeployement", ETH_ADDRESS, 0).unwrap(),
+            "m/2645'/579218131'/891216374'/1961790679'/2135936222'/0"
+        );
+        // Path the starknet-rs Ledger signer documents for layer "starknet", app "starkli".
+        assert_eq!(eip2645_path("starknet", "starkli", "0x0", 0).unwrap(), "m/2645'/1195502025'/1470455285'/0'/0'/0");
+        assert!(eip2645_path("starkex", "starkdeployement", "0x1a4864d977b944315389d1765ffa7e66f74ee8cd7", 0).is_err());
+    }
+
+    #[test]
+    fn grind_key_matches_starkex() {
+        let seed = hex::decode("86F3E7293141F20A8BAFF320E8EE4ACCB9D4A4BF2B4D295E8CEE784DB46E0519").unwrap();
+        assert_eq!(grind_key(&seed).unwrap(), felt("0x5c8c8683596c732541a59e03007b2d30dbbbb873556fe65b5fb63c16688f941"));
+    }
+
+    #[test]
+    fn mnemonic_keys_match_starkex() {
+        let wallet = HdWallet::from_mnemonic(MNEMONIC, "").unwrap();
+        for (index, expected) in [
+            (0, "0x6cf0a8bf113352eb863157a45c5e5567abb34f8d32cddafd2c22aa803f4892c"),
+            (7, "0x341751bdc42841da35ab74d13a1372c1f0250617e8a2ef96034d9f46e6847af"),
+            (598, "0x41a4d591a868353d28b7947eb132aa4d00c4a022743689ffd20a3628d6ca28c"),
+        ] {
+            let path = eip2645_path("starkex", "starkdeployement", ETH_ADDRESS, index).unwrap();
+            assert_eq!(wallet.derive_private_key(&path).unwrap(), felt(expected), "index {}", index);
+        }
+    }
+}
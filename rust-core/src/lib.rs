@@ -1,23 +1,186 @@
+// pyo3 0.20's #[pymethods] expands to impls that newer rustc flags as non-local.
+#![allow(non_local_definitions)]
+
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
 
 mod vault;
+mod audit;
+mod secret;
+mod shamir;
 mod starknet_client;
 mod supply_chain;
 mod rate_limiter;
 mod influence_api;
 mod session_keys;
+mod session_policy;
+mod revocation;
+mod hd_keys;
+mod typed_data;
+mod transaction;
+mod receipt;
+mod nonce_manager;
+mod state_file;
+mod provider_health;
+mod quorum;
+mod tokens;
+mod batch_query;
 
-use vault::Vault;
+use vault::{KdfParams, Vault, VaultError};
+use secret::SecretString;
 use starknet_client::StarknetClient;
 use supply_chain::{SupplyChainGraph, Recipe};
-use influence_api::{InfluenceClient, Asteroid};
+use influence_api::InfluenceClient;
 use session_keys::SessionKey;
+use session_policy::{PolicyViolation, SessionPolicy};
+use typed_data::TypedData;
+use revocation::RevocationRegistry;
+use hd_keys::HdWallet;
+use transaction::{FeeEstimate, InvokeSigner, InvokeV3, ResourceBound, ResourceBounds};
+use receipt::{TxOutcome, TxReceipt, WatchOptions, WatchTimeout};
+use provider_health::ProviderHealth;
+use quorum::{QuorumError, QuorumRead};
+use tokens::TokenBalance;
+use starknet::accounts::Call;
+use starknet::core::utils::{cairo_short_string_to_felt, get_selector_from_name};
+use starknet::core::crypto::Signature;
+use starknet::core::types::FieldElement;
 use std::collections::HashMap;
 
 // --- PyO3 Wrappers ---
 
+pyo3::create_exception!(stark_pyrust_chain, VaultIntegrityError, pyo3::exceptions::PyValueError);
+pyo3::create_exception!(stark_pyrust_chain, VaultLockedError, pyo3::exceptions::PyPermissionError);
+pyo3::create_exception!(stark_pyrust_chain, PolicyViolationError, pyo3::exceptions::PyPermissionError);
+pyo3::create_exception!(stark_pyrust_chain, QuorumDisagreementError, pyo3::exceptions::PyRuntimeError);
+
+/// Map vault failures to Python, keeping tamper/swap detection, wrong passwords and
+/// lockouts as their own exception types.
+fn vault_err(e: anyhow::Error) -> PyErr {
+    let message = format!("{:#}", e);
+    match e.chain().find_map(|cause| cause.downcast_ref::<VaultError>()) {
+        Some(VaultError::EntryAuthentication { .. }) => VaultIntegrityError::new_err(message),
+        Some(VaultError::WrongPassword) => pyo3::exceptions::PyPermissionError::new_err(message),
+        Some(VaultError::LockedOut { .. }) => VaultLockedError::new_err(message),
+        Some(VaultError::Busy) => pyo3::exceptions::PyBlockingIOError::new_err(message),
+        None => pyo3::exceptions::PyValueError::new_err(message),
+    }
+}
+
+fn parse_felt(value: &str) -> PyResult<FieldElement> {
+    FieldElement::from_hex_be(value.trim())
+        .map_err(|_| pyo3::exceptions::PyValueError::new_err(format!("Invalid felt: {}", value)))
+}
+
+/// Accept either a hex selector or an entry-point name such as `"transfer"`.
+fn parse_selector(value: &str) -> PyResult<FieldElement> {
+    if value.trim().starts_with("0x") {
+        parse_felt(value)
+    } else {
+        get_selector_from_name(value.trim())
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err(format!("Invalid entry point name: {}", value)))
+    }
+}
+
+/// Map session-key failures to Python, surfacing policy refusals as `PolicyViolationError`.
+fn session_err(e: anyhow::Error) -> PyErr {
+    if e.chain().any(|cause| cause.is::<PolicyViolation>()) {
+        PolicyViolationError::new_err(format!("{:#}", e))
+    } else {
+        pyo3::exceptions::PyValueError::new_err(format!("{:#}", e))
+    }
+}
+
+/// `[(to, selector, [calldata...]), ...]` from Python into calls.
+fn parse_calls(calls: &[(String, String, Vec<String>)]) -> PyResult<Vec<Call>> {
+    calls.iter()
+        .map(|(to, selector, calldata)| Ok(Call {
+            to: parse_felt(to)?,
+            selector: parse_selector(selector)?,
+            calldata: calldata.iter().map(|d| parse_felt(d)).collect::<PyResult<_>>()?,
+        }))
+        .collect()
+}
+
+/// Map transaction failures to Python: policy refusals and vault errors keep their
+/// exception types, RPC failures become `RuntimeError`.
+fn execute_err(e: anyhow::Error) -> PyErr {
+    if e.chain().any(|cause| cause.is::<PolicyViolation>()) {
+        session_err(e)
+    } else if e.chain().any(|cause| cause.is::<VaultError>()) {
+        vault_err(e)
+    } else {
+        pyo3::exceptions::PyRuntimeError::new_err(format!("{:#}", e))
+    }
+}
+
+/// Map watcher failures to Python: a timeout is `TimeoutError`, anything else
+/// `RuntimeError`.
+fn watch_err(e: anyhow::Error) -> PyErr {
+    if e.chain().any(|cause| cause.is::<WatchTimeout>()) {
+        pyo3::exceptions::PyTimeoutError::new_err(format!("{:#}", e))
+    } else {
+        pyo3::exceptions::PyRuntimeError::new_err(format!("{:#}", e))
+    }
+}
+
+/// Map quorum failures to Python. A disagreement is `QuorumDisagreementError` with
+/// `dissenters` (provider indices), `answers` (index -> answer), `endpoints`
+/// (index -> redacted endpoint) and `block_number` attributes; anything else is
+/// `RuntimeError`. Indices, not endpoints, key the maps: two providers on the same
+/// host redact to the same endpoint.
+fn quorum_err(py: Python<'_>, e: anyhow::Error) -> PyErr {
+    let message = format!("{:#}", e);
+    match e.chain().find_map(|cause| cause.downcast_ref::<QuorumError>()) {
+        Some(QuorumError::Disagreement { block_number, answers, dissenters, .. }) => {
+            let err = QuorumDisagreementError::new_err(message);
+            let endpoints: HashMap<usize, &str> = answers.iter()
+                .map(|a| (a.provider, a.endpoint.as_str()))
+                .collect();
+            let answers: HashMap<usize, &str> = answers.iter()
+                .map(|a| (a.provider, a.answer.as_str()))
+                .collect();
+            let value = err.value(py);
+            // Setting attributes on a fresh exception instance cannot fail.
+            let _ = value.setattr("dissenters", dissenters.clone());
+            let _ = value.setattr("answers", answers);
+            let _ = value.setattr("endpoints", endpoints);
+            let _ = value.setattr("block_number", *block_number);
+            err
+        }
+        _ => pyo3::exceptions::PyRuntimeError::new_err(message),
+    }
+}
+
+/// Python int from a decimal string, for amounts wider than 128 bits.
+fn py_int(py: Python<'_>, decimal: &str) -> PyResult<PyObject> {
+    Ok(py.get_type::<pyo3::types::PyLong>().call1((decimal,))?.into())
+}
+
+/// Decrypted secret handed to Python. The plaintext only becomes a Python `str`
+/// when `expose()` is called; `repr()` and `str()` stay redacted.
+#[pyclass]
+struct PySecret {
+    inner: SecretString,
+}
+
+#[pymethods]
+impl PySecret {
+    fn expose(&self) -> String {
+        self.inner.expose_secret().to_string()
+    }
+
+    fn __repr__(&self) -> &'static str {
+        "<PySecret [REDACTED]>"
+    }
+
+    fn __str__(&self) -> &'static str {
+        "[REDACTED]"
+    }
+}
+
 #[pyclass]
 struct PyVault {
     inner: Vault,
@@ -35,11 +198,371 @@ impl PyVault {
         self.inner.encrypt(plaintext).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
     }
 
-    fn decrypt(&self, ciphertext: &str) -> PyResult<String> {
-        self.inner.decrypt(ciphertext).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    fn decrypt(&self, ciphertext: &str) -> PyResult<PySecret> {
+        self.inner.decrypt(ciphertext)
+            .map(|inner| PySecret { inner })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Open (or create) the encrypted named-secret store at `path`, e.g. `vault.bin`.
+    #[staticmethod]
+    fn open(path: &str, password: &str) -> PyResult<Self> {
+        let vault = Vault::open(path, password).map_err(vault_err)?;
+        Ok(PyVault { inner: vault })
+    }
+
+    /// Open the vault with a password rebuilt from any `threshold` Shamir shares.
+    #[staticmethod]
+    fn open_from_shares(path: &str, shares: Vec<String>) -> PyResult<Self> {
+        let vault = Vault::open_with_shares(path, &shares).map_err(vault_err)?;
+        Ok(PyVault { inner: vault })
+    }
+
+    /// Split a vault password into `shares` checksummed text shares, `threshold` of which recover it.
+    #[staticmethod]
+    fn split_password(password: &str, threshold: u8, shares: u8) -> PyResult<Vec<String>> {
+        shamir::split_password(password, threshold, shares).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Check the hash chain of a vault audit log (`vault.bin.audit`).
+    /// Returns (valid_records, first_broken_line, reason).
+    #[staticmethod]
+    fn verify_audit_log(path: &str) -> PyResult<(u64, Option<u64>, Option<String>)> {
+        let report = audit::verify_chain(path).map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("{:#}", e)))?;
+        Ok((report.records, report.first_broken, report.reason))
+    }
+
+    /// Unlock the vault injected via `STARK_VAULT_BLOB` / `STARK_VAULT_PASSWORD` (read-only).
+    #[staticmethod]
+    fn from_env() -> PyResult<Self> {
+        let vault = Vault::from_env().map_err(vault_err)?;
+        Ok(PyVault { inner: vault })
+    }
+
+    /// Base64 of the encrypted vault file, for storing as the `STARK_VAULT_BLOB` CI secret.
+    fn export_blob(&self) -> PyResult<String> {
+        self.inner.export_blob().map_err(vault_err)
+    }
+
+    fn get_secret(&self, name: &str) -> PyResult<Option<PySecret>> {
+        self.inner.get_secret(name)
+            .map(|secret| secret.map(|inner| PySecret { inner }))
+            .map_err(vault_err)
+    }
+
+    fn set_secret(&mut self, name: &str, value: &str) -> PyResult<()> {
+        self.inner.set_secret(name, value).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{:#}", e)))
+    }
+
+    fn delete_secret(&mut self, name: &str) -> PyResult<bool> {
+        self.inner.delete_secret(name).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{:#}", e)))
+    }
+
+    fn list_secrets(&self) -> PyResult<Vec<String>> {
+        self.inner.list_secrets().map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Import a starkli-style JSON keystore into entry `name`. Returns the public key.
+    fn import_keystore(&mut self, keystore_path: &str, keystore_password: &str, name: &str) -> PyResult<String> {
+        self.inner.import_keystore(keystore_path, keystore_password, name).map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("{:#}", e)))
+    }
+
+    fn export_keystore(&self, name: &str, keystore_path: &str, keystore_password: &str) -> PyResult<()> {
+        self.inner.export_keystore(name, keystore_path, keystore_password).map_err(vault_err)
+    }
+
+    /// Sign a felt hash with the Stark key stored as `name`. Returns `(r, s)` as hex.
+    fn sign_hash(&self, name: &str, hash: &str) -> PyResult<(String, String)> {
+        let signature = self.inner.sign_hash(name, &parse_felt(hash)?).map_err(vault_err)?;
+        Ok((format!("{:#064x}", signature.r), format!("{:#064x}", signature.s)))
+    }
+
+    /// Rotate the vault password, re-encrypting every stored secret. Keeps the current
+    /// Argon2id cost unless `kdf=(m_cost_kib, t_cost, p_cost)` is given. No backup that
+    /// opens with the old password is kept.
+    #[pyo3(signature = (old_password, new_password, kdf=None))]
+    fn rekey(&mut self, old_password: &str, new_password: &str, kdf: Option<(u32, u32, u32)>) -> PyResult<()> {
+        let kdf = kdf.map(|(m_cost_kib, t_cost, p_cost)| KdfParams::Argon2id { m_cost_kib, t_cost, p_cost });
+        self.inner.rekey(old_password, new_password, kdf).map_err(vault_err)
+    }
+}
+
+/// Fee breakdown of an estimate or simulation. Amounts are integers in `unit`
+/// (`"FRI"` for STRK-paid v3 transactions). `revert_reason` is only set by `simulate`.
+#[pyclass]
+struct PyFeeEstimate {
+    #[pyo3(get)]
+    l1_gas_consumed: u128,
+    #[pyo3(get)]
+    l1_gas_price: u128,
+    #[pyo3(get)]
+    l1_data_gas_consumed: u128,
+    #[pyo3(get)]
+    l1_data_gas_price: u128,
+    #[pyo3(get)]
+    l2_gas_consumed: u128,
+    #[pyo3(get)]
+    l2_gas_price: u128,
+    #[pyo3(get)]
+    overall_fee: u128,
+    #[pyo3(get)]
+    unit: String,
+    #[pyo3(get)]
+    revert_reason: Option<String>,
+}
+
+impl PyFeeEstimate {
+    fn new(fee: FeeEstimate, revert_reason: Option<String>) -> Self {
+        PyFeeEstimate {
+            l1_gas_consumed: fee.l1_gas_consumed,
+            l1_gas_price: fee.l1_gas_price,
+            l1_data_gas_consumed: fee.l1_data_gas_consumed,
+            l1_data_gas_price: fee.l1_data_gas_price,
+            l2_gas_consumed: fee.l2_gas_consumed,
+            l2_gas_price: fee.l2_gas_price,
+            overall_fee: fee.overall_fee,
+            unit: fee.unit,
+            revert_reason,
+        }
+    }
+}
+
+#[pymethods]
+impl PyFeeEstimate {
+    fn __repr__(&self) -> String {
+        format!(
+            "PyFeeEstimate(overall_fee={} {}, l1_gas={}, l1_data_gas={}, l2_gas={}, revert_reason={:?})",
+            self.overall_fee, self.unit, self.l1_gas_consumed, self.l1_data_gas_consumed,
+            self.l2_gas_consumed, self.revert_reason
+        )
+    }
+}
+
+/// Final state of a watched transaction. `status` is `ACCEPTED_ON_L2`,
+/// `ACCEPTED_ON_L1`, `REVERTED` or `REJECTED`; `actual_fee` is in `fee_unit`.
+#[pyclass]
+struct PyTxReceipt {
+    #[pyo3(get)]
+    transaction_hash: String,
+    #[pyo3(get)]
+    status: String,
+    #[pyo3(get)]
+    block_number: Option<u64>,
+    #[pyo3(get)]
+    actual_fee: u128,
+    #[pyo3(get)]
+    fee_unit: String,
+    #[pyo3(get)]
+    revert_reason: Option<String>,
+    #[pyo3(get)]
+    steps: u64,
+    #[pyo3(get)]
+    l1_gas: u64,
+    #[pyo3(get)]
+    l1_data_gas: u64,
+    #[pyo3(get)]
+    l2_gas: u64,
+    outcome: TxOutcome,
+}
+
+impl From<TxReceipt> for PyTxReceipt {
+    fn from(receipt: TxReceipt) -> Self {
+        PyTxReceipt {
+            transaction_hash: format!("{:#064x}", receipt.transaction_hash),
+            status: receipt.outcome.as_str().to_string(),
+            block_number: receipt.block_number,
+            actual_fee: receipt.actual_fee,
+            fee_unit: receipt.fee_unit,
+            revert_reason: receipt.revert_reason,
+            steps: receipt.execution_resources.steps,
+            l1_gas: receipt.execution_resources.l1_gas,
+            l1_data_gas: receipt.execution_resources.l1_data_gas,
+            l2_gas: receipt.execution_resources.l2_gas,
+            outcome: receipt.outcome,
+        }
+    }
+}
+
+#[pymethods]
+impl PyTxReceipt {
+    /// True when the transaction was accepted and its execution succeeded.
+    #[getter]
+    fn succeeded(&self) -> bool {
+        matches!(self.outcome, TxOutcome::AcceptedOnL2 | TxOutcome::AcceptedOnL1)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "PyTxReceipt({}, status={}, block={}, fee={} {}, revert_reason={})",
+            self.transaction_hash,
+            self.outcome,
+            self.block_number.map_or("None".to_string(), |b| b.to_string()),
+            self.actual_fee,
+            self.fee_unit,
+            self.revert_reason.as_deref().map_or("None".to_string(), |r| format!("{:?}", r))
+        )
+    }
+}
+
+/// One row of `PyStarknetClient.get_provider_health()`.
+#[pyclass]
+struct PyProviderHealth {
+    #[pyo3(get)]
+    endpoint: String,
+    #[pyo3(get)]
+    healthy: bool,
+    #[pyo3(get)]
+    latency_ms: Option<f64>,
+    #[pyo3(get)]
+    error_rate: f64,
+    #[pyo3(get)]
+    requests: u64,
+    #[pyo3(get)]
+    failures: u64,
+    #[pyo3(get)]
+    consecutive_failures: u32,
+    #[pyo3(get)]
+    last_block: Option<u64>,
+    #[pyo3(get)]
+    chain_id: Option<String>,
+    /// None until the provider's chain id was checked.
+    #[pyo3(get)]
+    chain_id_ok: Option<bool>,
+    /// Seconds left on the cool-down, 0 when not benched.
+    #[pyo3(get)]
+    cooldown_secs: f64,
+    #[pyo3(get)]
+    last_error: Option<String>,
+    /// None until a JSON-RPC batch was tried on this provider.
+    #[pyo3(get)]
+    supports_batch: Option<bool>,
+}
+
+impl From<ProviderHealth> for PyProviderHealth {
+    fn from(health: ProviderHealth) -> Self {
+        let now = std::time::Instant::now();
+        PyProviderHealth {
+            healthy: health.is_healthy(now),
+            cooldown_secs: health.cooldown_until
+                .map_or(0.0, |until| until.saturating_duration_since(now).as_secs_f64()),
+            chain_id: health.chain_id.map(|id| format!("{:#x}", id)),
+            endpoint: health.endpoint,
+            latency_ms: health.latency_ewma_ms,
+            error_rate: health.error_rate,
+            requests: health.requests,
+            failures: health.failures,
+            consecutive_failures: health.consecutive_failures,
+            last_block: health.last_block,
+            chain_id_ok: health.chain_id_ok,
+            last_error: health.last_error,
+            supports_batch: health.supports_batch,
+        }
+    }
+}
+
+#[pymethods]
+impl PyProviderHealth {
+    fn __repr__(&self) -> String {
+        format!(
+            "PyProviderHealth({}, healthy={}, latency_ms={}, error_rate={:.2}, last_block={})",
+            self.endpoint,
+            self.healthy,
+            self.latency_ms.map_or("None".to_string(), |ms| format!("{:.0}", ms)),
+            self.error_rate,
+            self.last_block.map_or("None".to_string(), |b| b.to_string())
+        )
+    }
+}
+
+/// A value the providers of a quorum agreed on.
+#[pyclass]
+struct PyQuorumRead {
+    #[pyo3(get)]
+    value: PyObject,
+    #[pyo3(get)]
+    block_number: u64,
+    /// Endpoints that voted.
+    #[pyo3(get)]
+    providers: Vec<String>,
+    /// Endpoints too far behind the highest block to vote.
+    #[pyo3(get)]
+    lagging: Vec<String>,
+}
+
+impl PyQuorumRead {
+    fn new<T: ToPyObject>(py: Python<'_>, read: QuorumRead<T>) -> Self {
+        PyQuorumRead {
+            value: read.value.to_object(py),
+            block_number: read.block_number,
+            providers: read.providers,
+            lagging: read.lagging,
+        }
+    }
+}
+
+#[pymethods]
+impl PyQuorumRead {
+    fn __repr__(&self, py: Python<'_>) -> String {
+        let value = self.value.as_ref(py).repr().map(|r| r.to_string()).unwrap_or_default();
+        format!(
+            "PyQuorumRead({}, block={}, providers={:?}, lagging={:?})",
+            value, self.block_number, self.providers, self.lagging
+        )
+    }
+}
+
+/// An ERC-20 balance. `amount` is the exact u256 in base units; `formatted` and
+/// `value` are shifted by the token's decimals.
+#[pyclass]
+struct PyTokenBalance {
+    #[pyo3(get)]
+    symbol: String,
+    #[pyo3(get)]
+    decimals: u8,
+    #[pyo3(get)]
+    token_address: String,
+    #[pyo3(get)]
+    owner: String,
+    #[pyo3(get)]
+    formatted: String,
+    raw_amount: String,
+}
+
+impl From<TokenBalance> for PyTokenBalance {
+    fn from(balance: TokenBalance) -> Self {
+        PyTokenBalance {
+            formatted: balance.formatted(),
+            raw_amount: balance.amount.to_string(),
+            symbol: balance.token.symbol,
+            decimals: balance.token.decimals,
+            token_address: format!("{:#064x}", balance.token.address),
+            owner: format!("{:#064x}", balance.owner),
+        }
+    }
+}
+
+#[pymethods]
+impl PyTokenBalance {
+    /// Exact balance in base units (wei, fri, ...) as a Python int.
+    #[getter]
+    fn amount(&self, py: Python<'_>) -> PyResult<PyObject> {
+        py_int(py, &self.raw_amount)
+    }
+
+    /// Balance in whole tokens, as a float for display and thresholds.
+    #[getter]
+    fn value(&self) -> f64 {
+        self.formatted.parse().unwrap_or(f64::NAN)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PyTokenBalance({} {}, owner={})", self.formatted, self.symbol, self.owner)
     }
 }
 
+/// `(chain_nonce, [(nonce, tx_hash)], gaps)` as returned by `get_nonce_report`.
+type NonceReport = (u64, Vec<(u64, Option<String>)>, Vec<u64>);
+
 #[pyclass]
 struct PyStarknetClient {
     inner: Arc<StarknetClient>,
@@ -68,14 +591,61 @@ impl PyStarknetClient {
     fn get_eth_balance(&self, address: &str) -> PyResult<u128> {
         self.rt.block_on(async {
             self.inner.get_eth_balance(address).await
-        }).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+        }).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{:#}", e)))
     }
 
-    fn batch_query(&self, account: &str, asteroids: Vec<u64>) -> PyResult<String> {
+    /// `owner`'s balance of `token`: `"ETH"`, `"STRK"`, `"USDC"`, `"SWAY"` or any ERC-20
+    /// address.
+    fn get_token_balance(&self, token: &str, owner: &str) -> PyResult<PyTokenBalance> {
+        let owner = parse_felt(owner)?;
         self.rt.block_on(async {
+            self.inner.get_token_balance(token, owner).await
+        }).map(PyTokenBalance::from)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{:#}", e)))
+    }
+
+    /// `[(symbol, address, decimals), ...]` of the registry tokens known on the
+    /// connected network.
+    fn get_known_tokens(&self) -> PyResult<Vec<(String, String, u8)>> {
+        let tokens = self.rt.block_on(async {
+            self.inner.known_tokens().await
+        }).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{:#}", e)))?;
+        Ok(tokens.into_iter().map(|t| (t.symbol, format!("{:#064x}", t.address), t.decimals)).collect())
+    }
+
+    /// SWAY balance of `account` and the owner of each asteroid, read at one block:
+    /// `{"account", "block_number", "batched", "sway": {"symbol", "decimals",
+    /// "amount", "formatted"}, "asteroid_owners": [{"id", "owner", "owned", "error"}]}`.
+    /// Only ownership is read, not lot state.
+    fn batch_query(&self, py: Python<'_>, account: &str, asteroids: Vec<u64>) -> PyResult<PyObject> {
+        let result = self.rt.block_on(async {
             self.inner.batch_query(account, &asteroids).await
-                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
-        })
+        }).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{:#}", e)))?;
+
+        let sway = PyDict::new(py);
+        sway.set_item("symbol", &result.sway.token.symbol)?;
+        sway.set_item("decimals", result.sway.token.decimals)?;
+        sway.set_item("amount", py_int(py, &result.sway.amount.to_string())?)?;
+        sway.set_item("formatted", result.sway.formatted())?;
+
+        let asteroid_owners = result.asteroid_owners.iter()
+            .map(|asteroid| {
+                let entry = PyDict::new(py);
+                entry.set_item("id", asteroid.asteroid_id)?;
+                entry.set_item("owner", asteroid.owner.map(|o| format!("{:#064x}", o)))?;
+                entry.set_item("owned", asteroid.owned_by(result.account))?;
+                entry.set_item("error", &asteroid.error)?;
+                Ok(entry)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let out = PyDict::new(py);
+        out.set_item("account", format!("{:#064x}", result.account))?;
+        out.set_item("block_number", result.block_number)?;
+        out.set_item("batched", result.batched)?;
+        out.set_item("sway", sway)?;
+        out.set_item("asteroid_owners", asteroid_owners)?;
+        Ok(out.into())
     }
 
     fn get_nonce(&self, address: &str) -> PyResult<String> {
@@ -84,11 +654,183 @@ impl PyStarknetClient {
         }).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
     }
 
+    /// `(chain_nonce, [(nonce, tx_hash or None), ...], gaps)` for `address`: nonces this
+    /// machine has reserved that have not landed yet, and the missing nonces that
+    /// block them.
+    fn get_nonce_report(&self, address: &str) -> PyResult<NonceReport> {
+        let account = parse_felt(address)?;
+        let (chain_nonce, pending, gaps) = self.rt.block_on(async {
+            self.inner.nonce_report(account).await
+        }).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{:#}", e)))?;
+        let pending = pending.into_iter().map(|r| (r.nonce, r.tx_hash)).collect();
+        Ok((chain_nonce, pending, gaps))
+    }
+
+    /// Health of every configured RPC provider, in configuration order: latency and
+    /// error-rate averages, last block seen, chain-id check and cool-down.
+    fn get_provider_health(&self) -> Vec<PyProviderHealth> {
+        self.inner.provider_health().into_iter().map(PyProviderHealth::from).collect()
+    }
+
+    /// Nonce of `address` read from `quorum` providers at the same block. Raises
+    /// `QuorumDisagreementError` when they answer differently.
+    #[pyo3(signature = (address, quorum=2))]
+    fn get_nonce_quorum(&self, py: Python<'_>, address: &str, quorum: usize) -> PyResult<PyQuorumRead> {
+        let account = parse_felt(address)?;
+        let inner = self.inner.clone();
+        let handle = self.rt.handle().clone();
+        let read = py.allow_threads(move || {
+            handle.block_on(async { inner.quorum_nonce(account, quorum).await })
+        }).map_err(|e| quorum_err(py, e))?;
+        let read = read.try_map(|nonce| Ok(u64::try_from(nonce)?))
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Nonce out of range: {}", e)))?;
+        Ok(PyQuorumRead::new(py, read))
+    }
+
+    /// `function(calldata)` on `contract` read from `quorum` providers at the same
+    /// block; `value` is the list of result felts (hex). Use it for balances or crew
+    /// state before spending. Raises `QuorumDisagreementError` when they disagree.
+    #[pyo3(signature = (contract, function, calldata, quorum=2))]
+    fn call_quorum(
+        &self,
+        py: Python<'_>,
+        contract: &str,
+        function: &str,
+        calldata: Vec<String>,
+        quorum: usize,
+    ) -> PyResult<PyQuorumRead> {
+        let contract = parse_felt(contract)?;
+        let selector = parse_selector(function)?;
+        let calldata = calldata.iter().map(|d| parse_felt(d)).collect::<PyResult<Vec<_>>>()?;
+        let inner = self.inner.clone();
+        let handle = self.rt.handle().clone();
+        let read = py.allow_threads(move || {
+            handle.block_on(async { inner.quorum_call(contract, selector, &calldata, quorum).await })
+        }).map_err(|e| quorum_err(py, e))?;
+        let read = read.map(|felts| felts.iter().map(|f| format!("{:#x}", f)).collect::<Vec<_>>());
+        Ok(PyQuorumRead::new(py, read))
+    }
+
     fn get_crew_status(&self, crew_id: u64) -> PyResult<(bool, u8)> {
          self.rt.block_on(async {
             self.inner.get_crew_status(crew_id).await
         }).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
     }
+
+    /// Block until `tx_hash` is accepted, reverted or rejected and return its receipt.
+    /// Raises `TimeoutError` after `timeout_secs`. The GIL is released while waiting.
+    #[pyo3(signature = (tx_hash, timeout_secs=300, poll_interval_secs=5))]
+    fn wait_for_transaction(&self, py: Python, tx_hash: &str, timeout_secs: u64, poll_interval_secs: u64) -> PyResult<PyTxReceipt> {
+        let hash = parse_felt(tx_hash)?;
+        let options = WatchOptions {
+            timeout: std::time::Duration::from_secs(timeout_secs),
+            poll_interval: std::time::Duration::from_secs(poll_interval_secs),
+        };
+        let inner = self.inner.clone();
+        let handle = self.rt.handle().clone();
+        let receipt = py.allow_threads(move || {
+            handle.block_on(async { inner.wait_for_transaction(hash, options).await })
+        }).map_err(watch_err)?;
+        Ok(receipt.into())
+    }
+
+    /// Watch `tx_hash` in the background and call `callback(receipt, error)` from a
+    /// worker thread when it settles: `(PyTxReceipt, None)` on completion,
+    /// `(None, exception)` on timeout or RPC failure. Returns immediately; the client
+    /// must stay alive until the callback has run.
+    #[pyo3(signature = (tx_hash, callback, timeout_secs=300, poll_interval_secs=5))]
+    fn watch_transaction(&self, tx_hash: &str, callback: PyObject, timeout_secs: u64, poll_interval_secs: u64) -> PyResult<()> {
+        let hash = parse_felt(tx_hash)?;
+        let options = WatchOptions {
+            timeout: std::time::Duration::from_secs(timeout_secs),
+            poll_interval: std::time::Duration::from_secs(poll_interval_secs),
+        };
+        let inner = self.inner.clone();
+        self.rt.spawn(async move {
+            let result = inner.wait_for_transaction(hash, options).await;
+            Python::with_gil(|py| {
+                let called = match result {
+                    Ok(receipt) => callback.call1(py, (PyTxReceipt::from(receipt), py.None())),
+                    Err(e) => callback.call1(py, (py.None(), watch_err(e))),
+                };
+                if let Err(e) = called {
+                    e.print(py);
+                }
+            });
+        });
+        Ok(())
+    }
+
+    /// Estimated fee of `calls` from `account_address`; raises `RuntimeError` with the
+    /// node's execution error if they would revert.
+    fn estimate_fee(&self, account_address: &str, calls: Vec<(String, String, Vec<String>)>) -> PyResult<PyFeeEstimate> {
+        let account = parse_felt(account_address)?;
+        let calls = parse_calls(&calls)?;
+        let fee = self.rt.block_on(async {
+            self.inner.estimate_fee(account, &calls).await
+        }).map_err(execute_err)?;
+        Ok(PyFeeEstimate::new(fee, None))
+    }
+
+    /// Simulate `calls` from `account_address` with validation skipped. A revert does
+    /// not raise: check `revert_reason` on the result before signing.
+    fn simulate(&self, account_address: &str, calls: Vec<(String, String, Vec<String>)>) -> PyResult<PyFeeEstimate> {
+        let account = parse_felt(account_address)?;
+        let calls = parse_calls(&calls)?;
+        let simulation = self.rt.block_on(async {
+            self.inner.simulate(account, &calls).await
+        }).map_err(execute_err)?;
+        Ok(PyFeeEstimate::new(simulation.fee, simulation.revert_reason))
+    }
+
+    /// Submit `calls` from `account_address` as an INVOKE v3 transaction signed with the
+    /// account key stored in `vault` under `key_name`. Returns the transaction hash.
+    #[pyo3(signature = (account_address, calls, vault, key_name, fee_multiplier=1.5))]
+    fn execute(
+        &self,
+        account_address: &str,
+        calls: Vec<(String, String, Vec<String>)>,
+        vault: &PyVault,
+        key_name: &str,
+        fee_multiplier: f64,
+    ) -> PyResult<String> {
+        let account = parse_felt(account_address)?;
+        let calls = parse_calls(&calls)?;
+        let mut signer = InvokeSigner::Vault { vault: &vault.inner, key_name };
+        let hash = self.rt.block_on(async {
+            self.inner.execute(account, &calls, &mut signer, fee_multiplier).await
+        }).map_err(execute_err)?;
+        Ok(format!("{:#064x}", hash))
+    }
+
+    /// Same as `execute`, signed by a session key, so its policy and the revocation list
+    /// apply (`PolicyViolationError`). The account contract must accept the session
+    /// key's `[r, s]` signature. A key bound to a vault entry (`load_from_vault`,
+    /// `rotate_in_vault`, `save_to_vault`) writes its spend totals back there.
+    #[pyo3(signature = (account_address, calls, session_key, fee_multiplier=1.5))]
+    fn execute_with_session(
+        &self,
+        py: Python<'_>,
+        account_address: &str,
+        calls: Vec<(String, String, Vec<String>)>,
+        session_key: &mut PySessionKey,
+        fee_multiplier: f64,
+    ) -> PyResult<String> {
+        let account = parse_felt(account_address)?;
+        let calls = parse_calls(&calls)?;
+        let mut vault;
+        let mut signer = match &session_key.vault_entry {
+            Some((stored_in, name)) => {
+                vault = stored_in.borrow_mut(py);
+                InvokeSigner::StoredSession { key: &mut session_key.inner, vault: &mut vault.inner, name }
+            }
+            None => InvokeSigner::Session(&mut session_key.inner),
+        };
+        let hash = self.rt.block_on(async {
+            self.inner.execute(account, &calls, &mut signer, fee_multiplier).await
+        }).map_err(execute_err)?;
+        Ok(format!("{:#064x}", hash))
+    }
 }
 
 #[pyclass]
@@ -166,6 +908,15 @@ impl PyInfluenceClient {
 #[pyclass]
 struct PySessionKey {
     inner: SessionKey,
+    /// Vault entry the key was loaded from or saved to. Signing through a bound key
+    /// writes its spend totals back there.
+    vault_entry: Option<(Py<PyVault>, String)>,
+}
+
+impl From<SessionKey> for PySessionKey {
+    fn from(inner: SessionKey) -> Self {
+        PySessionKey { inner, vault_entry: None }
+    }
 }
 
 #[pymethods]
@@ -173,24 +924,301 @@ impl PySessionKey {
     #[new]
     fn new() -> PyResult<Self> {
         let key = SessionKey::generate().map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
-        Ok(PySessionKey { inner: key })
+        Ok(PySessionKey::from(key))
     }
 
     fn get_public_key(&self) -> String {
-        self.inner.public_key.clone()
+        format!("{:#064x}", self.inner.public_key)
     }
     
-    fn create_auth_payload(&self, master_account: &str) -> String {
-        self.inner.create_authorization_payload(master_account)
+    #[staticmethod]
+    fn from_private_key(private_key: &str) -> PyResult<Self> {
+        let scalar = parse_felt(private_key)?;
+        let key = SessionKey::from_private_key(scalar).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PySessionKey::from(key))
+    }
+
+    /// Sign a felt message hash. Returns `(r, s)` as hex strings. A key with a policy
+    /// raises `PolicyViolationError`: it only signs transactions (`sign_invoke`,
+    /// `PyStarknetClient.execute_with_session`).
+    fn sign(&self, hash: &str) -> PyResult<(String, String)> {
+        let signature = self.inner.sign_hash(&parse_felt(hash)?).map_err(session_err)?;
+        Ok((format!("{:#064x}", signature.r), format!("{:#064x}", signature.s)))
+    }
+
+    /// Attach a policy. `allowed_calls` maps each contract address to the selectors
+    /// (hex or entry-point names) the key may call on that contract; `spend_limits` maps
+    /// token address to the maximum cumulative amount (token base units).
+    #[pyo3(signature = (allowed_calls, spend_limits, valid_after=0, valid_until=0))]
+    fn set_policy(
+        &mut self,
+        allowed_calls: HashMap<String, Vec<String>>,
+        spend_limits: HashMap<String, u128>,
+        valid_after: u64,
+        valid_until: u64,
+    ) -> PyResult<()> {
+        let policy = SessionPolicy {
+            allowed_calls: allowed_calls.iter()
+                .map(|(contract, selectors)| Ok((
+                    parse_felt(contract)?,
+                    selectors.iter().map(|s| parse_selector(s)).collect::<PyResult<_>>()?,
+                )))
+                .collect::<PyResult<_>>()?,
+            spend_limits: spend_limits.iter()
+                .map(|(token, limit)| Ok((parse_felt(token)?, *limit)))
+                .collect::<PyResult<_>>()?,
+            valid_after,
+            valid_until,
+        };
+        self.inner.set_policy(policy);
+        Ok(())
+    }
+
+    fn get_expires_at(&self) -> u64 {
+        self.inner.expires_at
+    }
+
+    /// Persist this key (with its policy and spend totals) as vault entry `name` and
+    /// bind it there: later signatures write the updated spend totals back.
+    fn save_to_vault(&mut self, py: Python<'_>, vault: Py<PyVault>, name: &str) -> PyResult<()> {
+        self.inner.save_to_vault(&mut vault.borrow_mut(py).inner, name).map_err(vault_err)?;
+        self.vault_entry = Some((vault, name.to_string()));
+        Ok(())
+    }
+
+    /// Load a key saved with `save_to_vault`, bound to that entry.
+    #[staticmethod]
+    fn load_from_vault(py: Python<'_>, vault: Py<PyVault>, name: &str) -> PyResult<Option<PySessionKey>> {
+        let key = SessionKey::load_from_vault(&vault.borrow(py).inner, name).map_err(vault_err)?;
+        Ok(key.map(|inner| PySessionKey { inner, vault_entry: Some((vault, name.to_string())) }))
+    }
+
+    /// True once the key is within `margin_secs` of its expiry.
+    fn needs_rotation(&self, margin_secs: u64) -> PyResult<bool> {
+        let now = state_file::unix_now()
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+        Ok(self.inner.needs_rotation(now, margin_secs))
+    }
+
+    /// Load the key stored as `name`, replacing it if missing or near expiry.
+    /// Returns `(key, authorization)`, where `authorization` is `(typed_data_json, hash)`
+    /// for a newly created key and `None` otherwise. The key is bound to the entry.
+    #[staticmethod]
+    #[pyo3(signature = (vault, name, master_account, lifetime_secs, margin_secs, chain_id="SN_MAIN"))]
+    fn rotate_in_vault(
+        py: Python<'_>,
+        vault: Py<PyVault>,
+        name: &str,
+        master_account: &str,
+        lifetime_secs: u64,
+        margin_secs: u64,
+        chain_id: &str,
+    ) -> PyResult<(PySessionKey, Option<(String, String)>)> {
+        let rotation = session_keys::rotate_in_vault(
+            &mut vault.borrow_mut(py).inner, name, parse_felt(master_account)?, chain_id, lifetime_secs, margin_secs,
+        ).map_err(vault_err)?;
+        let authorization = match rotation.authorization {
+            Some((typed_data, hash)) => Some((typed_data.to_json().map_err(session_err)?, format!("{:#064x}", hash))),
+            None => None,
+        };
+        Ok((PySessionKey { inner: rotation.key, vault_entry: Some((vault, name.to_string())) }, authorization))
+    }
+
+    /// Build and sign the INVOKE v3 transaction executing `calls` =
+    /// `[(to, selector, [calldata...]), ...]` from `sender_address`, raising
+    /// `PolicyViolationError` if any call is outside the policy. Bounds are
+    /// `(max_amount, max_price_per_unit)`. Returns `(tx_hash, r, s)`. A key bound to a
+    /// vault entry writes its spend totals back there.
+    #[pyo3(signature = (sender_address, calls, nonce, chain_id="SN_MAIN", l1_gas=(0, 0), l2_gas=(0, 0), l1_data_gas=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn sign_invoke(
+        &mut self,
+        py: Python<'_>,
+        sender_address: &str,
+        calls: Vec<(String, String, Vec<String>)>,
+        nonce: u64,
+        chain_id: &str,
+        l1_gas: (u64, u128),
+        l2_gas: (u64, u128),
+        l1_data_gas: Option<(u64, u128)>,
+    ) -> PyResult<(String, String, String)> {
+        let bound = |(max_amount, max_price_per_unit): (u64, u128)| ResourceBound { max_amount, max_price_per_unit };
+        let chain_id = cairo_short_string_to_felt(chain_id)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid chain id: {}", e)))?;
+        let mut tx = InvokeV3::new(parse_felt(sender_address)?, &parse_calls(&calls)?, FieldElement::from(nonce), chain_id);
+        tx.resource_bounds = ResourceBounds {
+            l1_gas: bound(l1_gas),
+            l2_gas: bound(l2_gas),
+            l1_data_gas: l1_data_gas.map(bound),
+        };
+        let hash = tx.hash().map_err(session_err)?;
+        let signature = match &self.vault_entry {
+            Some((vault, name)) => self.inner.sign_invoke_in_vault(&tx, &mut vault.borrow_mut(py).inner, name),
+            None => self.inner.sign_invoke(&tx),
+        }.map_err(session_err)?;
+        Ok((format!("{:#064x}", hash), format!("{:#064x}", signature.r), format!("{:#064x}", signature.s)))
+    }
+
+    #[staticmethod]
+    fn verify(public_key: &str, hash: &str, r: &str, s: &str) -> PyResult<bool> {
+        let signature = Signature { r: parse_felt(r)?, s: parse_felt(s)? };
+        session_keys::verify(&parse_felt(public_key)?, &parse_felt(hash)?, &signature)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// SNIP-12 session authorization for `master_account`. Returns `(typed_data_json, hash)`;
+    /// the master account signs `hash` (e.g. with `PyVault.sign_hash`).
+    #[pyo3(signature = (master_account, chain_id="SN_MAIN"))]
+    fn create_auth_payload(&self, master_account: &str, chain_id: &str) -> PyResult<(String, String)> {
+        let (typed_data, hash) = self.inner.create_authorization_payload(parse_felt(master_account)?, chain_id)
+            .map_err(session_err)?;
+        let json = typed_data.to_json().map_err(session_err)?;
+        Ok((json, format!("{:#064x}", hash)))
+    }
+
+    /// Off-chain check of a master-account signature over a session authorization.
+    #[staticmethod]
+    fn verify_authorization(typed_data_json: &str, master_account: &str, master_public_key: &str, r: &str, s: &str) -> PyResult<bool> {
+        let typed_data = TypedData::from_json(typed_data_json).map_err(session_err)?;
+        let signature = Signature { r: parse_felt(r)?, s: parse_felt(s)? };
+        session_keys::verify_typed_data(&typed_data, parse_felt(master_account)?, &parse_felt(master_public_key)?, &signature)
+            .map_err(session_err)
+    }
+}
+
+/// Local list of revoked session keys, checked by every `PySessionKey` signing call.
+#[pyclass]
+struct PyRevocationRegistry {
+    inner: RevocationRegistry,
+}
+
+#[pymethods]
+impl PyRevocationRegistry {
+    /// Open the registry at `path`, or at `$STARK_REVOCATION_LIST` (the one signing
+    /// checks, an absolute path) when omitted.
+    #[new]
+    #[pyo3(signature = (path=None))]
+    fn new(path: Option<&str>) -> PyResult<Self> {
+        let inner = match path {
+            Some(path) => RevocationRegistry::new(path),
+            None => RevocationRegistry::from_env()
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("{:#}", e)))?,
+        };
+        Ok(PyRevocationRegistry { inner })
+    }
+
+    fn revoke(&self, public_key: &str, reason: &str) -> PyResult<()> {
+        self.inner.revoke(parse_felt(public_key)?, reason)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("{:#}", e)))
+    }
+
+    fn is_revoked(&self, public_key: &str) -> PyResult<bool> {
+        let found = self.inner.find(&parse_felt(public_key)?)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("{:#}", e)))?;
+        Ok(found.is_some())
+    }
+
+    /// Returns `[(public_key, reason, revoked_at), ...]`.
+    fn list(&self) -> PyResult<Vec<(String, String, u64)>> {
+        let revoked = self.inner.list()
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("{:#}", e)))?;
+        Ok(revoked.into_iter()
+            .map(|r| (format!("{:#064x}", r.public_key), r.reason, r.revoked_at))
+            .collect())
+    }
+
+    /// Revoked keys as `Array<felt252>` calldata (`[len, key_0, ...]`) in hex.
+    fn revocation_calldata(&self) -> PyResult<Vec<String>> {
+        let calldata = self.inner.revocation_calldata()
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("{:#}", e)))?;
+        Ok(calldata.iter().map(|felt| format!("{:#064x}", felt)).collect())
+    }
+}
+
+/// Stark keys regenerated from one BIP-39 mnemonic. Private keys only ever leave
+/// Rust into a `PyVault` entry or a `PySessionKey`.
+#[pyclass]
+struct PyHdWallet {
+    inner: HdWallet,
+}
+
+#[pymethods]
+impl PyHdWallet {
+    #[staticmethod]
+    fn generate_mnemonic() -> PySecret {
+        PySecret { inner: SecretString::from(HdWallet::generate_mnemonic().as_str()) }
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (phrase, passphrase=""))]
+    fn from_mnemonic(phrase: &str, passphrase: &str) -> PyResult<Self> {
+        let inner = HdWallet::from_mnemonic(phrase, passphrase)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyHdWallet { inner })
+    }
+
+    /// Raw BIP-32 seed as hex (16 to 64 bytes).
+    #[staticmethod]
+    fn from_seed(seed_hex: &str) -> PyResult<Self> {
+        let seed = zeroize::Zeroizing::new(hex::decode(seed_hex.trim().trim_start_matches("0x"))
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Seed must be hex"))?);
+        let inner = HdWallet::from_seed(&seed)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyHdWallet { inner })
+    }
+
+    /// Build an EIP-2645 path, e.g. `("starknet", "myapp", "0x0", 3)`.
+    #[staticmethod]
+    fn eip2645_path(layer: &str, application: &str, eth_address: &str, index: u32) -> PyResult<String> {
+        hd_keys::eip2645_path(layer, application, eth_address, index)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn derive_public_key(&self, path: &str) -> PyResult<String> {
+        let public_key = self.inner.derive_public_key(path)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(format!("{:#064x}", public_key))
+    }
+
+    /// Public key of account `index` (Braavos path).
+    fn account_public_key(&self, index: u32) -> PyResult<String> {
+        self.derive_public_key(&format!("{}/{}", hd_keys::ACCOUNT_PATH_PREFIX, index))
+    }
+
+    /// Derive account key `index` straight into vault entry `name`. Returns the public key.
+    fn store_account_key(&self, vault: &mut PyVault, name: &str, index: u32) -> PyResult<String> {
+        let private_key = self.inner.account_private_key(index)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        let hex = zeroize::Zeroizing::new(format!("{:#064x}", private_key));
+        vault.inner.set_secret(name, &hex).map_err(vault_err)?;
+        self.account_public_key(index)
+    }
+
+    fn session_key(&self, index: u32) -> PyResult<PySessionKey> {
+        let inner = self.inner.session_key(index)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PySessionKey::from(inner))
     }
 }
 
 #[pymodule]
-fn stark_pyrust_chain(_py: Python, m: &PyModule) -> PyResult<()> {
+fn stark_pyrust_chain(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyVault>()?;
+    m.add_class::<PySecret>()?;
+    m.add("VaultIntegrityError", py.get_type::<VaultIntegrityError>())?;
+    m.add("VaultLockedError", py.get_type::<VaultLockedError>())?;
+    m.add("PolicyViolationError", py.get_type::<PolicyViolationError>())?;
+    m.add("QuorumDisagreementError", py.get_type::<QuorumDisagreementError>())?;
     m.add_class::<PyStarknetClient>()?;
+    m.add_class::<PyFeeEstimate>()?;
+    m.add_class::<PyTxReceipt>()?;
+    m.add_class::<PyProviderHealth>()?;
+    m.add_class::<PyQuorumRead>()?;
+    m.add_class::<PyTokenBalance>()?;
     m.add_class::<PySupplyChain>()?;
     m.add_class::<PyInfluenceClient>()?;
     m.add_class::<PySessionKey>()?;
+    m.add_class::<PyRevocationRegistry>()?;
+    m.add_class::<PyHdWallet>()?;
     Ok(())
 }
@@ -0,0 +1,145 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::FieldElement;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::state_file::{absolute_path_env, unix_now, StateFile};
+
+/// Overrides the nonce state location shared by every process on this machine. Must
+/// be absolute, like the default, so every pulse finds the same file whatever its
+/// working directory.
+pub const NONCE_STATE_PATH_ENV: &str = "STARK_NONCE_STATE";
+/// File name under the per-user state directory used when the override is unset.
+pub const DEFAULT_NONCE_STATE_FILE: &str = "stark_pyrust_chain/nonce_state.json";
+/// A reservation that was never submitted is assumed abandoned (crashed pulse) after this.
+pub const UNSUBMITTED_TIMEOUT_SECS: u64 = 300;
+
+/// A nonce held by one transaction of one process.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reservation {
+    pub nonce: u64,
+    pub reserved_at: u64,
+    pub pid: u32,
+    /// Set once the transaction was handed to a node.
+    #[serde(default)]
+    pub tx_hash: Option<String>,
+    #[serde(default)]
+    pub submitted_at: Option<u64>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct NonceState {
+    /// Account address (`{:#064x}`) -> outstanding reservations.
+    accounts: BTreeMap<String, Vec<Reservation>>,
+}
+
+/// Cross-process nonce reservations for the accounts this machine signs for. Every
+/// change happens under an exclusive file lock, so two overlapping pulses can never
+/// be handed the same nonce.
+pub struct NonceManager {
+    state: StateFile<NonceState>,
+}
+
+impl NonceManager {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        NonceManager { state: StateFile::new(path, "nonce state") }
+    }
+
+    /// State at `$STARK_NONCE_STATE`, or [`DEFAULT_NONCE_STATE_FILE`] under
+    /// `$XDG_STATE_HOME` (falling back to `~/.local/state`).
+    pub fn from_env() -> Result<Self> {
+        let path = match absolute_path_env(NONCE_STATE_PATH_ENV)? {
+            Some(path) => path,
+            None => default_state_path()?,
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context(format!("Failed to create nonce state directory {}", dir.display()))?;
+        }
+        Ok(Self::new(path))
+    }
+
+    /// Reserve the lowest nonce at or above `chain_nonce` that no live reservation holds.
+    /// Reservations below the chain nonce are dropped (their transactions landed), as
+    /// are unsubmitted ones older than [`UNSUBMITTED_TIMEOUT_SECS`].
+    pub fn reserve(&self, account: FieldElement, chain_nonce: u64) -> Result<u64> {
+        let now = unix_now()?;
+        self.update(account, |reservations| {
+            reservations.retain(|r| {
+                r.nonce >= chain_nonce
+                    && (r.tx_hash.is_some() || now.saturating_sub(r.reserved_at) < UNSUBMITTED_TIMEOUT_SECS)
+            });
+
+            let mut nonce = chain_nonce;
+            while reservations.iter().any(|r| r.nonce == nonce) {
+                nonce += 1;
+            }
+            reservations.push(Reservation {
+                nonce,
+                reserved_at: now,
+                pid: std::process::id(),
+                tx_hash: None,
+                submitted_at: None,
+            });
+            reservations.sort_by_key(|r| r.nonce);
+            nonce
+        })
+    }
+
+    /// Record that the transaction holding `nonce` was sent as `tx_hash`.
+    pub fn mark_submitted(&self, account: FieldElement, nonce: u64, tx_hash: FieldElement) -> Result<()> {
+        let now = unix_now()?;
+        self.update(account, |reservations| {
+            if let Some(r) = reservations.iter_mut().find(|r| r.nonce == nonce) {
+                r.tx_hash = Some(format!("{:#064x}", tx_hash));
+                r.submitted_at = Some(now);
+            }
+        })
+    }
+
+    /// Give `nonce` back: its transaction was never sent, or was dropped or rejected.
+    pub fn release(&self, account: FieldElement, nonce: u64) -> Result<()> {
+        self.update(account, |reservations| reservations.retain(|r| r.nonce != nonce))
+    }
+
+    pub fn reservations(&self, account: FieldElement) -> Result<Vec<Reservation>> {
+        Ok(self.state.read()?.accounts.remove(&account_key(account)).unwrap_or_default())
+    }
+
+    /// Nonces between `chain_nonce` and the highest reservation that nobody holds.
+    /// Any transaction above a gap cannot execute until the gap is filled, which the
+    /// next [`Self::reserve`] does.
+    pub fn gaps(&self, account: FieldElement, chain_nonce: u64) -> Result<Vec<u64>> {
+        let reservations = self.reservations(account)?;
+        let Some(highest) = reservations.iter().map(|r| r.nonce).max() else { return Ok(Vec::new()) };
+        Ok((chain_nonce..highest)
+            .filter(|n| !reservations.iter().any(|r| r.nonce == *n))
+            .collect())
+    }
+
+    fn update<T>(&self, account: FieldElement, change: impl FnOnce(&mut Vec<Reservation>) -> T) -> Result<T> {
+        self.state.update(|state| {
+            let key = account_key(account);
+            let reservations = state.accounts.entry(key.clone()).or_default();
+            let result = change(reservations);
+            if reservations.is_empty() {
+                state.accounts.remove(&key);
+            }
+            Ok(result)
+        })
+    }
+}
+
+fn account_key(account: FieldElement) -> String {
+    format!("{:#064x}", account)
+}
+
+fn default_state_path() -> Result<PathBuf> {
+    let state_home = match absolute_path_env("XDG_STATE_HOME")? {
+        Some(dir) => dir,
+        None => absolute_path_env("HOME")?
+            .map(|home| home.join(".local/state"))
+            .context(format!("Neither {} nor HOME is set; cannot place the nonce state", NONCE_STATE_PATH_ENV))?,
+    };
+    Ok(state_home.join(DEFAULT_NONCE_STATE_FILE))
+}
@@ -0,0 +1,181 @@
+use starknet::core::types::FieldElement;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// Weight of the newest sample in the latency and error-rate averages.
+const EWMA_ALPHA: f64 = 0.2;
+/// Consecutive failures before a provider is benched.
+const FAILURES_BEFORE_COOLDOWN: u32 = 2;
+const COOLDOWN_BASE: Duration = Duration::from_secs(15);
+const COOLDOWN_MAX: Duration = Duration::from_secs(600);
+/// A provider this many blocks behind the best one seen is only used as a last resort.
+pub const MAX_BLOCK_LAG: u64 = 10;
+
+/// Rolling health of one RPC endpoint.
+#[derive(Debug, Clone)]
+pub struct ProviderHealth {
+    /// Scheme, host and port only: paths and queries often carry API keys.
+    pub endpoint: String,
+    pub latency_ewma_ms: Option<f64>,
+    pub error_rate: f64,
+    pub requests: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    pub last_block: Option<u64>,
+    pub chain_id: Option<FieldElement>,
+    /// `Some(false)` once the endpoint answered with another network's chain id; it is
+    /// never used again.
+    pub chain_id_ok: Option<bool>,
+    pub cooldown_until: Option<Instant>,
+    pub last_error: Option<String>,
+    /// Whether the endpoint takes JSON-RPC batch requests; `None` until tried.
+    pub supports_batch: Option<bool>,
+}
+
+impl ProviderHealth {
+    fn new(url: &Url) -> Self {
+        ProviderHealth {
+            endpoint: match url.port() {
+                Some(port) => format!("{}://{}:{}", url.scheme(), url.host_str().unwrap_or("?"), port),
+                None => format!("{}://{}", url.scheme(), url.host_str().unwrap_or("?")),
+            },
+            latency_ewma_ms: None,
+            error_rate: 0.0,
+            requests: 0,
+            failures: 0,
+            consecutive_failures: 0,
+            last_block: None,
+            chain_id: None,
+            chain_id_ok: None,
+            cooldown_until: None,
+            last_error: None,
+            supports_batch: None,
+        }
+    }
+
+    pub fn cooling_down(&self, now: Instant) -> bool {
+        self.cooldown_until.is_some_and(|until| until > now)
+    }
+
+    /// Usable right now: on the right chain and not benched.
+    pub fn is_healthy(&self, now: Instant) -> bool {
+        self.chain_id_ok != Some(false) && !self.cooling_down(now)
+    }
+}
+
+/// Health table of every provider of a `StarknetClient`, consulted on each request
+/// to skip dead, lagging or wrong-chain endpoints.
+pub struct HealthTracker {
+    entries: Vec<Mutex<ProviderHealth>>,
+    /// Full URLs, only to scrub them out of recorded errors.
+    urls: Vec<String>,
+}
+
+impl HealthTracker {
+    pub fn new(urls: &[Url]) -> Self {
+        HealthTracker {
+            entries: urls.iter().map(|u| Mutex::new(ProviderHealth::new(u))).collect(),
+            urls: urls.iter().map(|u| u.to_string()).collect(),
+        }
+    }
+
+    fn entry(&self, idx: usize) -> std::sync::MutexGuard<'_, ProviderHealth> {
+        // A panic while holding the lock cannot leave the numbers inconsistent enough
+        // to matter, so recover from poisoning.
+        self.entries[idx].lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn endpoint(&self, idx: usize) -> String {
+        self.entry(idx).endpoint.clone()
+    }
+
+    pub fn snapshot(&self) -> Vec<ProviderHealth> {
+        (0..self.entries.len()).map(|i| self.entry(i).clone()).collect()
+    }
+
+    /// Provider order for one request, starting the rotation at `start` and skipping
+    /// `exclude`: healthy, up-to-date providers first (those with a confirmed chain id
+    /// ahead of unchecked ones, which may not answer at all), then healthy laggards,
+    /// then benched ones by soonest end of cool-down. Wrong-chain providers never appear.
+    pub fn candidates(&self, start: usize, exclude: &[usize]) -> Vec<usize> {
+        let now = Instant::now();
+        let table = self.snapshot();
+        let best_block = table.iter().filter_map(|h| h.last_block).max();
+        let lagging = |h: &ProviderHealth| match (best_block, h.last_block) {
+            (Some(best), Some(last)) => best.saturating_sub(last) > MAX_BLOCK_LAG,
+            _ => false,
+        };
+
+        let n = table.len();
+        let rotation: Vec<usize> = (0..n)
+            .map(|offset| (start + offset) % n)
+            .filter(|i| !exclude.contains(i) && table[*i].chain_id_ok != Some(false))
+            .collect();
+
+        let mut order: Vec<usize> = rotation.iter().copied()
+            .filter(|i| table[*i].is_healthy(now) && !lagging(&table[*i]))
+            .collect();
+        order.sort_by_key(|i| table[*i].chain_id_ok.is_none());
+        order.extend(rotation.iter().copied().filter(|i| table[*i].is_healthy(now) && lagging(&table[*i])));
+        let mut benched: Vec<usize> = rotation.iter().copied().filter(|i| !table[*i].is_healthy(now)).collect();
+        benched.sort_by_key(|i| table[*i].cooldown_until);
+        order.extend(benched);
+        order
+    }
+
+    pub fn record_success(&self, idx: usize, latency: Duration) {
+        let mut health = self.entry(idx);
+        health.requests += 1;
+        health.consecutive_failures = 0;
+        health.cooldown_until = None;
+        health.error_rate *= 1.0 - EWMA_ALPHA;
+        let ms = latency.as_secs_f64() * 1000.0;
+        health.latency_ewma_ms = Some(match health.latency_ewma_ms {
+            Some(avg) => avg + EWMA_ALPHA * (ms - avg),
+            None => ms,
+        });
+    }
+
+    /// Count a failure; from the second in a row the provider is benched for 15s,
+    /// doubling per further failure up to 10 minutes.
+    pub fn record_failure(&self, idx: usize, error: &anyhow::Error) {
+        let mut health = self.entry(idx);
+        health.requests += 1;
+        health.failures += 1;
+        health.consecutive_failures += 1;
+        health.error_rate = health.error_rate * (1.0 - EWMA_ALPHA) + EWMA_ALPHA;
+        health.last_error = Some(format!("{:#}", error).replace(&self.urls[idx], &health.endpoint));
+        if health.consecutive_failures >= FAILURES_BEFORE_COOLDOWN {
+            let doublings = (health.consecutive_failures - FAILURES_BEFORE_COOLDOWN).min(16);
+            let cooldown = COOLDOWN_BASE.saturating_mul(1 << doublings).min(COOLDOWN_MAX);
+            health.cooldown_until = Some(Instant::now() + cooldown);
+        }
+    }
+
+    pub fn record_block(&self, idx: usize, block: u64) {
+        let mut health = self.entry(idx);
+        health.last_block = Some(health.last_block.map_or(block, |b| b.max(block)));
+    }
+
+    pub fn batch_supported(&self, idx: usize) -> Option<bool> {
+        self.entry(idx).supports_batch
+    }
+
+    pub fn record_batch_support(&self, idx: usize, supported: bool) {
+        self.entry(idx).supports_batch = Some(supported);
+    }
+
+    pub fn chain_id_ok(&self, idx: usize) -> Option<bool> {
+        self.entry(idx).chain_id_ok
+    }
+
+    pub fn record_chain_id(&self, idx: usize, chain_id: FieldElement, expected: FieldElement) {
+        let mut health = self.entry(idx);
+        health.chain_id = Some(chain_id);
+        health.chain_id_ok = Some(chain_id == expected);
+        if chain_id != expected {
+            health.last_error = Some(format!("Chain id {:#x} does not match expected {:#x}", chain_id, expected));
+        }
+    }
+}
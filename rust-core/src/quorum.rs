@@ -0,0 +1,134 @@
+use starknet::core::types::FieldElement;
+use std::fmt;
+use thiserror::Error;
+
+/// A decoded quorum result, compared between providers in its canonical text form so
+/// two encodings of the same value (`0x01` and `0x1`) agree.
+pub trait QuorumValue {
+    fn canonical(&self) -> String;
+}
+
+impl QuorumValue for FieldElement {
+    fn canonical(&self) -> String {
+        format!("{:#x}", self)
+    }
+}
+
+impl QuorumValue for Vec<FieldElement> {
+    fn canonical(&self) -> String {
+        format!("[{}]", self.iter().map(|felt| felt.canonical()).collect::<Vec<_>>().join(", "))
+    }
+}
+
+/// What one provider answered in a quorum read: the canonical result, or the code of
+/// the Starknet error it returned. Providers are told apart by index, since redacted
+/// endpoints on the same host look alike.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuorumAnswer {
+    pub provider: usize,
+    pub endpoint: String,
+    pub answer: String,
+}
+
+impl fmt::Display for QuorumAnswer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{} {} => {}", self.provider, self.endpoint, self.answer)
+    }
+}
+
+/// A value every provider of the quorum agreed on, read at the same block.
+#[derive(Debug, Clone)]
+pub struct QuorumRead<T> {
+    pub value: T,
+    pub block_number: u64,
+    /// Endpoints that voted.
+    pub providers: Vec<String>,
+    /// Endpoints more than `MAX_BLOCK_LAG` blocks behind the highest head; left out
+    /// of the vote.
+    pub lagging: Vec<String>,
+}
+
+impl<T> QuorumRead<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> QuorumRead<U> {
+        QuorumRead {
+            value: f(self.value),
+            block_number: self.block_number,
+            providers: self.providers,
+            lagging: self.lagging,
+        }
+    }
+
+    pub fn try_map<U>(self, f: impl FnOnce(T) -> anyhow::Result<U>) -> anyhow::Result<QuorumRead<U>> {
+        let value = f(self.value)?;
+        Ok(QuorumRead {
+            value,
+            block_number: self.block_number,
+            providers: self.providers,
+            lagging: self.lagging,
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum QuorumError {
+    /// The providers returned different answers for the same block: at least one is
+    /// lying, corrupt or serving another chain state.
+    #[error("{method}: providers disagree at block {block_number} (dissenting: {}): {}",
+        dissenters.iter().map(|p| format!("#{}", p)).collect::<Vec<_>>().join(", "),
+        answers.iter().map(|a| a.to_string()).collect::<Vec<_>>().join("; "))]
+    Disagreement {
+        method: String,
+        block_number: u64,
+        answers: Vec<QuorumAnswer>,
+        /// Providers outside the largest group of matching answers.
+        dissenters: Vec<usize>,
+    },
+    #[error("{method}: quorum of {needed} not reached, {available} up-to-date provider(s) available{}",
+        if lagging.is_empty() { String::new() } else { format!(" (lagging: {})", lagging.join(", ")) })]
+    NotEnoughProviders {
+        method: String,
+        needed: usize,
+        available: usize,
+        lagging: Vec<String>,
+    },
+}
+
+/// Providers whose answer differs from the one most voters gave (ties go to the
+/// earliest voter), or `None` when everyone agreed.
+pub fn dissenters(answers: &[QuorumAnswer]) -> Option<Vec<usize>> {
+    let votes = |answer: &str| answers.iter().filter(|a| a.answer == answer).count();
+    let majority = answers.iter()
+        .enumerate()
+        .max_by_key(|(i, a)| (votes(&a.answer), std::cmp::Reverse(*i)))
+        .map(|(_, a)| a.answer.clone())?;
+    let dissenting: Vec<usize> = answers.iter()
+        .filter(|a| a.answer != majority)
+        .map(|a| a.provider)
+        .collect();
+    (!dissenting.is_empty()).then_some(dissenting)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(provider: usize, answer: &str) -> QuorumAnswer {
+        QuorumAnswer { provider, endpoint: "https://rpc.example/***".to_string(), answer: answer.to_string() }
+    }
+
+    #[test]
+    fn felt_encodings_agree() {
+        let padded = FieldElement::from_hex_be("0x0000A").unwrap();
+        assert_eq!(padded.canonical(), "0xa");
+        assert_eq!(vec![padded, FieldElement::ONE].canonical(), "[0xa, 0x1]");
+    }
+
+    #[test]
+    fn dissenters_are_told_apart_by_provider() {
+        let answers = [answer(0, "0x1"), answer(1, "0x1"), answer(2, "0x2")];
+        assert_eq!(dissenters(&answers), Some(vec![2]));
+        assert_eq!(dissenters(&answers[..2]), None);
+        // A tie goes to the earliest voter.
+        assert_eq!(dissenters(&[answer(3, "0x2"), answer(1, "0x1")]), Some(vec![1]));
+    }
+}
@@ -0,0 +1,149 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use starknet::core::types::FieldElement;
+use std::fmt;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Where a watched transaction ended up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxOutcome {
+    AcceptedOnL2,
+    AcceptedOnL1,
+    /// Included in a block, but `__execute__` failed; the fee was still charged.
+    Reverted,
+    /// Never included (failed validation, bad nonce, insufficient balance, ...).
+    Rejected,
+}
+
+impl TxOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TxOutcome::AcceptedOnL2 => "ACCEPTED_ON_L2",
+            TxOutcome::AcceptedOnL1 => "ACCEPTED_ON_L1",
+            TxOutcome::Reverted => "REVERTED",
+            TxOutcome::Rejected => "REJECTED",
+        }
+    }
+}
+
+impl fmt::Display for TxOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Resources a transaction consumed. Fields a node's RPC version does not report
+/// stay 0: `steps` is gone from RPC 0.8, `l2_gas` only exists from 0.8 on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionResources {
+    pub steps: u64,
+    pub l1_gas: u64,
+    pub l1_data_gas: u64,
+    pub l2_gas: u64,
+}
+
+/// Final state of a transaction: outcome, fee actually paid and, for reverts and
+/// rejections, the reason.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxReceipt {
+    pub transaction_hash: FieldElement,
+    pub outcome: TxOutcome,
+    pub block_number: Option<u64>,
+    pub actual_fee: u128,
+    /// `"FRI"` (STRK) or `"WEI"` (ETH).
+    pub fee_unit: String,
+    pub revert_reason: Option<String>,
+    pub execution_resources: ExecutionResources,
+}
+
+impl TxReceipt {
+    /// Receipt from `starknet_getTransactionReceipt`.
+    pub fn from_rpc_json(value: &Value) -> Result<Self> {
+        let raw_hash = value.get("transaction_hash").and_then(Value::as_str)
+            .context("Receipt has no transaction_hash")?;
+        let transaction_hash = FieldElement::from_hex_be(raw_hash)
+            .map_err(|_| anyhow::anyhow!("Invalid transaction hash in receipt: {}", raw_hash))?;
+
+        let outcome = match (
+            value.get("execution_status").and_then(Value::as_str),
+            value.get("finality_status").and_then(Value::as_str),
+        ) {
+            (Some("REVERTED"), _) => TxOutcome::Reverted,
+            (_, Some("ACCEPTED_ON_L1")) => TxOutcome::AcceptedOnL1,
+            (_, Some("ACCEPTED_ON_L2")) => TxOutcome::AcceptedOnL2,
+            (_, other) => return Err(anyhow::anyhow!("Receipt is not final (finality_status: {:?})", other)),
+        };
+
+        // RPC 0.5 reports a bare WEI amount; 0.6+ a `{amount, unit}` object.
+        let (fee_amount, fee_unit) = match value.get("actual_fee") {
+            Some(Value::Object(fee)) => (
+                fee.get("amount").and_then(Value::as_str),
+                fee.get("unit").and_then(Value::as_str).unwrap_or("FRI"),
+            ),
+            Some(Value::String(amount)) => (Some(amount.as_str()), "WEI"),
+            _ => (None, "FRI"),
+        };
+        let actual_fee = match fee_amount {
+            Some(raw) => {
+                let felt = FieldElement::from_hex_be(raw)
+                    .map_err(|_| anyhow::anyhow!("Invalid actual_fee in receipt: {}", raw))?;
+                u128::try_from(felt).map_err(|_| anyhow::anyhow!("actual_fee overflows u128"))?
+            }
+            None => 0,
+        };
+
+        Ok(TxReceipt {
+            transaction_hash,
+            outcome,
+            block_number: value.get("block_number").and_then(Value::as_u64),
+            actual_fee,
+            fee_unit: fee_unit.to_string(),
+            revert_reason: value.get("revert_reason").and_then(Value::as_str).map(str::to_string),
+            execution_resources: parse_execution_resources(value.get("execution_resources")),
+        })
+    }
+
+    /// A transaction the sequencer rejected: it has no receipt, only its status.
+    pub fn rejected(transaction_hash: FieldElement, reason: Option<String>) -> Self {
+        TxReceipt {
+            transaction_hash,
+            outcome: TxOutcome::Rejected,
+            block_number: None,
+            actual_fee: 0,
+            fee_unit: "FRI".to_string(),
+            revert_reason: reason,
+            execution_resources: ExecutionResources::default(),
+        }
+    }
+}
+
+/// 0.6: `{steps, ...}`; 0.7: adds `data_availability: {l1_gas, l1_data_gas}`;
+/// 0.8: `{l1_gas, l1_data_gas, l2_gas}`.
+fn parse_execution_resources(value: Option<&Value>) -> ExecutionResources {
+    let Some(value) = value else { return ExecutionResources::default() };
+    let number = |v: &Value, key: &str| v.get(key).and_then(Value::as_u64);
+    let data_availability = value.get("data_availability").unwrap_or(&Value::Null);
+
+    ExecutionResources {
+        steps: number(value, "steps").unwrap_or_default(),
+        l1_gas: number(value, "l1_gas").or(number(data_availability, "l1_gas")).unwrap_or_default(),
+        l1_data_gas: number(value, "l1_data_gas").or(number(data_availability, "l1_data_gas")).unwrap_or_default(),
+        l2_gas: number(value, "l2_gas").unwrap_or_default(),
+    }
+}
+
+/// Polling schedule for [`crate::starknet_client::StarknetClient::wait_for_transaction`].
+#[derive(Debug, Clone, Copy)]
+pub struct WatchOptions {
+    pub timeout: Duration,
+    pub poll_interval: Duration,
+}
+
+#[derive(Debug, Error)]
+#[error("Timed out after {waited_secs}s waiting for transaction {transaction_hash:#064x} (last status: {last_status})")]
+pub struct WatchTimeout {
+    pub transaction_hash: FieldElement,
+    pub waited_secs: u64,
+    pub last_status: String,
+}
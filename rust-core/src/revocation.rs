@@ -0,0 +1,77 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize, Serializer};
+use starknet::core::types::FieldElement;
+use std::path::Path;
+use crate::state_file::{absolute_path_env, unix_now, StateFile};
+
+/// Absolute path of the registry every signing path checks. It only holds public keys,
+/// so it can be committed and read by the CI pulse. There is no default: a relative
+/// one would depend on the working directory, and a signer started elsewhere would
+/// see an empty list and sign with a revoked key.
+pub const REVOCATION_PATH_ENV: &str = "STARK_REVOCATION_LIST";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevokedKey {
+    #[serde(serialize_with = "serialize_hex")]
+    pub public_key: FieldElement,
+    pub reason: String,
+    pub revoked_at: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct RegistryFile {
+    revoked: Vec<RevokedKey>,
+}
+
+/// Local list of session public keys that must never sign again.
+pub struct RevocationRegistry {
+    file: StateFile<RegistryFile>,
+}
+
+impl RevocationRegistry {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        RevocationRegistry { file: StateFile::new(path, "revocation list") }
+    }
+
+    /// Registry at `$STARK_REVOCATION_LIST`. Fails when it is unset or relative, so
+    /// session keys refuse to sign rather than skip the check.
+    pub fn from_env() -> Result<Self> {
+        let path = absolute_path_env(REVOCATION_PATH_ENV)?
+            .context(format!("{} is not set; session keys do not sign without a revocation list", REVOCATION_PATH_ENV))?;
+        Ok(Self::new(path))
+    }
+
+    /// Revoke `public_key`. Revoking an already revoked key keeps the original record.
+    pub fn revoke(&self, public_key: FieldElement, reason: &str) -> Result<()> {
+        let revoked_at = unix_now()?;
+        self.file.update(|file| {
+            if !file.revoked.iter().any(|r| r.public_key == public_key) {
+                file.revoked.push(RevokedKey { public_key, reason: reason.to_string(), revoked_at });
+            }
+            Ok(())
+        })
+    }
+
+    pub fn list(&self) -> Result<Vec<RevokedKey>> {
+        Ok(self.file.read()?.revoked)
+    }
+
+    pub fn find(&self, public_key: &FieldElement) -> Result<Option<RevokedKey>> {
+        Ok(self.file.read()?.revoked.into_iter().find(|r| &r.public_key == public_key))
+    }
+
+    /// Revoked keys serialized as a Cairo `Array<felt252>` (`[len, key_0, key_1, ...]`),
+    /// ready to pass as calldata to an on-chain revocation entry point.
+    pub fn revocation_calldata(&self) -> Result<Vec<FieldElement>> {
+        let revoked = self.list()?;
+        let mut calldata = Vec::with_capacity(revoked.len() + 1);
+        calldata.push(FieldElement::from(revoked.len() as u64));
+        calldata.extend(revoked.iter().map(|r| r.public_key));
+        Ok(calldata)
+    }
+}
+
+/// Hex keeps the committed file readable; deserialization accepts hex or decimal.
+fn serialize_hex<S: Serializer>(felt: &FieldElement, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{:#064x}", felt))
+}
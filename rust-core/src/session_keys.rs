@@ -1,39 +1,374 @@
 use serde::{Deserialize, Serialize};
-use anyhow::Result;
+use serde_json::json;
+use anyhow::{Context, Result};
+use rand::{rngs::OsRng, RngCore};
+use starknet::core::crypto::{ecdsa_verify, Signature};
+use starknet::core::types::FieldElement;
+use starknet::signers::SigningKey;
+use std::collections::BTreeMap;
+use crate::revocation::RevocationRegistry;
+use crate::session_policy::{PolicyViolation, SessionPolicy};
+use crate::state_file::unix_now;
+use crate::transaction::{decode_calls, InvokeV3};
+use crate::typed_data::TypedData;
+use crate::vault::Vault;
+use zeroize::{Zeroize, Zeroizing};
 
-// In a real implementation, we would use:
-// use starknet::signers::{LocalWallet, SigningKey};
-// But for v0.1.0 compilation, we'll keep it simple/mocked.
+/// Order of the STARK curve's generator, big-endian. Private keys must lie in `[1, EC_ORDER)`.
+pub(crate) const EC_ORDER: [u8; 32] = [
+    0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xb7, 0x81, 0x12, 0x6d, 0xca, 0xe7, 0xb2, 0x32, 0x1e, 0x66, 0xa2, 0x41, 0xad, 0xc6, 0x4d, 0x2f,
+];
+
+/// SNIP-12 domain of session authorizations.
+const AUTH_DOMAIN_NAME: &str = "StarkPyRustChain";
+const AUTH_DOMAIN_VERSION: &str = "1";
 
 #[derive(Serialize, Deserialize)]
 pub struct SessionKey {
-    pub private_key: String,
-    pub public_key: String,
+    pub private_key: FieldElement,
+    pub public_key: FieldElement,
     pub expires_at: u64,
+    /// Without a policy the key may sign any call until `expires_at`.
+    #[serde(default)]
+    pub policy: Option<SessionPolicy>,
+    /// Cumulative amount signed away per token, checked against `policy.spend_limits`.
+    #[serde(default)]
+    pub spent: BTreeMap<FieldElement, u128>,
 }
 
 impl SessionKey {
-    /// Generate a new ephemeral session key.
-    /// In a real implementation, this would generate a Starknet-compatible key pair.
-    /// For this v0.1.0, we simulate the structure.
+    /// Generate a new ephemeral session key from the OS CSPRNG.
+    /// The public key is derived through starknet-rs's [`SigningKey`].
     pub fn generate() -> Result<Self> {
-        // Mock generation for compilation speed/compatibility without full crypto stack setup
-        
-        let priv_key_hex = "0x1234...ephemeral_private"; 
-        let pub_key_hex = "0x5678...ephemeral_public";
-        
+        let private_key = random_private_key();
+        let public_key = SigningKey::from_secret_scalar(private_key).verifying_key().scalar();
+
         Ok(SessionKey {
-            private_key: priv_key_hex.to_string(),
-            public_key: pub_key_hex.to_string(),
+            private_key,
+            public_key,
             expires_at: 0, // 0 = indefinite or set later
+            policy: None,
+            spent: BTreeMap::new(),
         })
     }
 
-    /// Create the signed payload that authorizes this session key on the Interact Contract.
-    pub fn create_authorization_payload(&self, master_account: &str) -> String {
-        format!(
-            "{{ \"master\": \"{}\", \"session_pub\": \"{}\", \"action\": \"AUTHORIZE\" }}",
-            master_account, self.public_key
-        )
+    /// Rebuild a session key from a known private key.
+    pub fn from_private_key(private_key: FieldElement) -> Result<Self> {
+        if private_key == FieldElement::ZERO || private_key.to_bytes_be() >= EC_ORDER {
+            return Err(anyhow::anyhow!("Session private key is outside the curve order"));
+        }
+        let public_key = SigningKey::from_secret_scalar(private_key).verifying_key().scalar();
+        Ok(SessionKey { private_key, public_key, expires_at: 0, policy: None, spent: BTreeMap::new() })
+    }
+
+    /// Store the key, its policy and spend totals as the vault entry `name`.
+    pub fn save_to_vault(&self, vault: &mut Vault, name: &str) -> Result<()> {
+        let json = Zeroizing::new(serde_json::to_string(self)?);
+        vault.set_secret(name, &json)
+    }
+
+    /// Load a key saved with [`SessionKey::save_to_vault`]; `None` if `name` is not stored.
+    pub fn load_from_vault(vault: &Vault, name: &str) -> Result<Option<Self>> {
+        match vault.get_secret(name)? {
+            Some(json) => {
+                let key = serde_json::from_str(json.expose_secret())
+                    .context(format!("Vault entry '{}' is not a session key", name))?;
+                Ok(Some(key))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// True once the key is within `margin_secs` of `expires_at` (never for keys
+    /// without an expiry).
+    pub fn needs_rotation(&self, now: u64, margin_secs: u64) -> bool {
+        self.expires_at != 0 && now.saturating_add(margin_secs) >= self.expires_at
+    }
+
+    /// A fresh key carrying this key's policy, valid from `now` for `lifetime_secs`.
+    /// Spend totals start again at zero.
+    pub fn successor(&self, now: u64, lifetime_secs: u64) -> Result<Self> {
+        let mut next = Self::generate()?;
+        match &self.policy {
+            Some(policy) => next.set_policy(SessionPolicy {
+                valid_after: now,
+                valid_until: now.saturating_add(lifetime_secs),
+                ..policy.clone()
+            }),
+            None => next.expires_at = now.saturating_add(lifetime_secs),
+        }
+        Ok(next)
+    }
+
+    /// Restrict what this key may sign. `expires_at` follows the policy's `valid_until`.
+    pub fn set_policy(&mut self, policy: SessionPolicy) {
+        self.expires_at = policy.valid_until;
+        self.policy = Some(policy);
+    }
+
+    /// ECDSA-sign a message hash on the STARK curve (RFC 6979 deterministic nonce).
+    /// Only for keys without a policy: a hash says nothing about the calls behind it,
+    /// so a policied key refuses and must sign through [`SessionKey::sign_invoke`].
+    pub fn sign_hash(&self, hash: &FieldElement) -> Result<Signature> {
+        if self.policy.is_some() {
+            return Err(PolicyViolation::RawHashRefused.into());
+        }
+        self.check_window(unix_now()?)?;
+        self.sign_unrevoked(hash)
+    }
+
+    /// Sign `tx`, failing with a [`PolicyViolation`] if any call in its calldata falls
+    /// outside the policy. The calls and the hash both come from `tx`, so what is
+    /// checked is what gets signed. Spend totals are only updated on success, and not
+    /// for query-only transactions.
+    pub fn sign_invoke(&mut self, tx: &InvokeV3) -> Result<Signature> {
+        let now = unix_now()?;
+        let totals = match &self.policy {
+            Some(policy) => Some(policy.check_calls(&decode_calls(&tx.calldata)?, &self.spent, now)?),
+            None => {
+                self.check_window(now)?;
+                None
+            }
+        };
+        let signature = self.sign_unrevoked(&tx.hash()?)?;
+        if let Some(totals) = totals.filter(|_| !tx.is_query) {
+            self.spent = totals;
+        }
+        Ok(signature)
+    }
+
+    /// [`SessionKey::sign_invoke`] for the key stored as vault entry `name`. The stored
+    /// spend totals are checked and the new ones written back under the vault lock, so
+    /// the cumulative caps hold across pulses and overlapping processes. On success this
+    /// key is replaced by the stored one.
+    pub fn sign_invoke_in_vault(&mut self, tx: &InvokeV3, vault: &mut Vault, name: &str) -> Result<Signature> {
+        let public_key = self.public_key;
+        let (stored, signature) = vault.update_secret(name, |current| {
+            let json = current.context(format!("Session key '{}' is no longer in the vault", name))?;
+            let mut stored: SessionKey = serde_json::from_str(json)
+                .context(format!("Vault entry '{}' is not a session key", name))?;
+            if stored.public_key != public_key {
+                return Err(anyhow::anyhow!("Vault entry '{}' holds a different session key (rotated?)", name));
+            }
+            let signature = stored.sign_invoke(tx)?;
+            Ok((Zeroizing::new(serde_json::to_string(&stored)?), (stored, signature)))
+        })?;
+        *self = stored;
+        Ok(signature)
+    }
+
+    fn check_window(&self, now: u64) -> Result<(), PolicyViolation> {
+        match &self.policy {
+            Some(policy) => policy.check_window(now),
+            None if self.expires_at != 0 && now >= self.expires_at => {
+                Err(PolicyViolation::Expired { expires_at: self.expires_at })
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Every signing path ends here, so a key in the revocation list never signs.
+    fn sign_unrevoked(&self, hash: &FieldElement) -> Result<Signature> {
+        if let Some(revoked) = RevocationRegistry::from_env()?.find(&self.public_key)? {
+            return Err(PolicyViolation::Revoked { public_key: self.public_key, reason: revoked.reason }.into());
+        }
+        SigningKey::from_secret_scalar(self.private_key)
+            .sign(hash)
+            .context("Failed to sign hash with session key")
+    }
+
+    /// SNIP-12 (revision 1) message authorizing this key and its policy for
+    /// `master_account` on `chain_id` (e.g. `SN_MAIN`). Returns the typed data and the
+    /// message hash the master account signs.
+    pub fn create_authorization_payload(&self, master_account: FieldElement, chain_id: &str) -> Result<(TypedData, FieldElement)> {
+        let policy = self.policy.clone().unwrap_or_default();
+        let hex = |felt: &FieldElement| format!("{:#064x}", felt);
+
+        let typed_data: TypedData = serde_json::from_value(json!({
+            "types": {
+                "StarknetDomain": [
+                    { "name": "name", "type": "shortstring" },
+                    { "name": "version", "type": "shortstring" },
+                    { "name": "chainId", "type": "shortstring" },
+                    { "name": "revision", "type": "shortstring" }
+                ],
+                "Session Authorization": [
+                    { "name": "Session Key", "type": "felt" },
+                    { "name": "Valid After", "type": "timestamp" },
+                    { "name": "Expires At", "type": "timestamp" },
+                    { "name": "Allowed Calls", "type": "Allowed Call*" },
+                    { "name": "Spend Limits", "type": "Spend Limit*" }
+                ],
+                "Allowed Call": [
+                    { "name": "Contract", "type": "ContractAddress" },
+                    { "name": "Selectors", "type": "felt*" }
+                ],
+                "Spend Limit": [
+                    { "name": "Token", "type": "ContractAddress" },
+                    { "name": "Amount", "type": "u128" }
+                ]
+            },
+            "primaryType": "Session Authorization",
+            "domain": {
+                "name": AUTH_DOMAIN_NAME,
+                "version": AUTH_DOMAIN_VERSION,
+                "chainId": chain_id,
+                "revision": "1"
+            },
+            "message": {
+                "Session Key": hex(&self.public_key),
+                "Valid After": policy.valid_after,
+                "Expires At": self.expires_at,
+                "Allowed Calls": policy.allowed_calls.iter()
+                    .map(|(contract, selectors)| json!({
+                        "Contract": hex(contract),
+                        "Selectors": selectors.iter().map(hex).collect::<Vec<_>>()
+                    }))
+                    .collect::<Vec<_>>(),
+                "Spend Limits": policy.spend_limits.iter()
+                    .map(|(token, amount)| json!({ "Token": hex(token), "Amount": amount.to_string() }))
+                    .collect::<Vec<_>>()
+            }
+        }))?;
+
+        let hash = typed_data.message_hash(master_account)?;
+        Ok((typed_data, hash))
+    }
+}
+
+/// Check that `signature` by `public_key` covers the SNIP-12 `typed_data` for `account`.
+pub fn verify_typed_data(typed_data: &TypedData, account: FieldElement, public_key: &FieldElement, signature: &Signature) -> Result<bool> {
+    verify(public_key, &typed_data.message_hash(account)?, signature)
+}
+
+/// Check an (r, s) signature over `hash` against a Stark public key.
+pub fn verify(public_key: &FieldElement, hash: &FieldElement, signature: &Signature) -> Result<bool> {
+    ecdsa_verify(public_key, hash, signature).context("Invalid signature values")
+}
+
+/// Result of [`rotate_in_vault`]. `authorization` is set whenever a new key was stored
+/// and still needs the master account's signature.
+pub struct Rotation {
+    pub key: SessionKey,
+    pub authorization: Option<(TypedData, FieldElement)>,
+}
+
+/// Load the session key stored as `name`, replacing it when it is missing, revoked or
+/// within `margin_secs` of expiry. Meant to run at the start of every pulse.
+pub fn rotate_in_vault(
+    vault: &mut Vault,
+    name: &str,
+    master_account: FieldElement,
+    chain_id: &str,
+    lifetime_secs: u64,
+    margin_secs: u64,
+) -> Result<Rotation> {
+    let now = unix_now()?;
+    let registry = RevocationRegistry::from_env()?;
+    let next = match SessionKey::load_from_vault(vault, name)? {
+        Some(current) if !current.needs_rotation(now, margin_secs) && registry.find(&current.public_key)?.is_none() => {
+            return Ok(Rotation { key: current, authorization: None });
+        }
+        Some(current) => current.successor(now, lifetime_secs)?,
+        None => {
+            let mut fresh = SessionKey::generate()?;
+            fresh.expires_at = now.saturating_add(lifetime_secs);
+            fresh
+        }
+    };
+
+    let authorization = next.create_authorization_payload(master_account, chain_id)?;
+    next.save_to_vault(vault, name)?;
+    Ok(Rotation { key: next, authorization: Some(authorization) })
+}
+
+/// Uniform scalar in `[1, EC_ORDER)` by rejection sampling 252-bit values.
+fn random_private_key() -> FieldElement {
+    let mut bytes = [0u8; 32];
+    loop {
+        OsRng.fill_bytes(&mut bytes);
+        bytes[0] &= 0x0f;
+        if bytes < EC_ORDER && bytes != [0u8; 32] {
+            if let Ok(candidate) = FieldElement::from_bytes_be(&bytes) {
+                bytes.zeroize();
+                return candidate;
+            }
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::revocation::REVOCATION_PATH_ENV;
+    use starknet::accounts::Call;
+    use starknet::core::utils::{cairo_short_string_to_felt, get_selector_from_name};
+    use std::path::PathBuf;
+    use std::sync::OnceLock;
+
+    /// Registry shared by every test in the process, since signing reads it from the
+    /// environment.
+    fn registry() -> RevocationRegistry {
+        static PATH: OnceLock<PathBuf> = OnceLock::new();
+        let path = PATH.get_or_init(|| {
+            let path = tempfile::tempdir().unwrap().keep().join("revoked_session_keys.json");
+            std::env::set_var(REVOCATION_PATH_ENV, &path);
+            path
+        });
+        RevocationRegistry::new(path)
+    }
+
+    fn token() -> FieldElement {
+        FieldElement::from_hex_be("0x70").unwrap()
+    }
+
+    fn policied_key() -> SessionKey {
+        let mut key = SessionKey::generate().unwrap();
+        key.set_policy(SessionPolicy {
+            allowed_calls: BTreeMap::from([(token(), vec![get_selector_from_name("transfer").unwrap()])]),
+            spend_limits: BTreeMap::from([(token(), 1000)]),
+            valid_after: 0,
+            valid_until: 0,
+        });
+        key
+    }
+
+    fn transfer(amount: u64) -> InvokeV3 {
+        let call = Call {
+            to: token(),
+            selector: get_selector_from_name("transfer").unwrap(),
+            calldata: vec![FieldElement::from(0x456u64), amount.into(), FieldElement::ZERO],
+        };
+        InvokeV3::new(FieldElement::from(0x123u64), &[call], FieldElement::ZERO, cairo_short_string_to_felt("SN_SEPOLIA").unwrap())
+    }
+
+    fn revoked(result: Result<Signature>) -> bool {
+        matches!(result.err().and_then(|e| e.downcast::<PolicyViolation>().ok()), Some(PolicyViolation::Revoked { .. }))
+    }
+
+    #[test]
+    fn revoked_key_cannot_sign_invoke() {
+        let registry = registry();
+        let mut key = policied_key();
+        key.sign_invoke(&transfer(100)).unwrap();
+
+        registry.revoke(key.public_key, "test").unwrap();
+        assert!(revoked(key.sign_invoke(&transfer(100))));
+        assert_eq!(key.spent[&token()], 100);
+    }
+
+    #[test]
+    fn revoked_key_cannot_sign_invoke_in_vault() {
+        let registry = registry();
+        let dir = tempfile::tempdir().unwrap();
+        let mut vault = Vault::open(dir.path().join("vault.bin"), "pw").unwrap();
+        let mut key = policied_key();
+        key.save_to_vault(&mut vault, "SESSION_KEY").unwrap();
+        key.sign_invoke_in_vault(&transfer(100), &mut vault, "SESSION_KEY").unwrap();
+
+        registry.revoke(key.public_key, "test").unwrap();
+        assert!(revoked(key.sign_invoke_in_vault(&transfer(100), &mut vault, "SESSION_KEY")));
+        let stored = SessionKey::load_from_vault(&vault, "SESSION_KEY").unwrap().unwrap();
+        assert_eq!(stored.spent[&token()], 100);
     }
 }
@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use starknet::accounts::Call;
+use starknet::core::types::FieldElement;
+use starknet::core::utils::get_selector_from_name;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// ERC-20 entry points whose `amount` counts against a spend limit, with the calldata
+/// index of the u256 `low` word.
+const SPEND_ENTRY_POINTS: &[(&str, usize)] = &[
+    ("transfer", 1),
+    ("approve", 1),
+    ("increase_allowance", 1),
+    ("increaseAllowance", 1),
+    ("transfer_from", 2),
+    ("transferFrom", 2),
+];
+
+/// Why a session key refused to sign.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum PolicyViolation {
+    #[error("Session key is not valid until {valid_after}")]
+    NotYetValid { valid_after: u64 },
+    #[error("Session key expired at {expires_at}")]
+    Expired { expires_at: u64 },
+    #[error("Contract {contract:#064x} is not allowed by the session policy")]
+    ContractNotAllowed { contract: FieldElement },
+    #[error("Selector {selector:#064x} on {contract:#064x} is not allowed by the session policy")]
+    SelectorNotAllowed { contract: FieldElement, selector: FieldElement },
+    #[error("Spend of {requested} on token {token:#064x} exceeds the remaining limit of {remaining}")]
+    SpendLimitExceeded { token: FieldElement, requested: u128, remaining: u128 },
+    #[error("Session key {public_key:#064x} was revoked: {reason}")]
+    Revoked { public_key: FieldElement, reason: String },
+    #[error("Malformed calldata for {selector:#064x} on {contract:#064x}")]
+    MalformedCalldata { contract: FieldElement, selector: FieldElement },
+    /// A key with a policy only signs transactions it can check against that policy.
+    #[error("Session key has a policy and does not sign raw hashes")]
+    RawHashRefused,
+}
+
+/// What a session key may sign. `allowed_calls` maps each contract the key may call to
+/// the selectors it may call there; a call is allowed only if its selector is listed for
+/// its own contract, and an empty map allows nothing. `spend_limits` caps the cumulative
+/// ERC-20 amount moved per token over the key's lifetime; tokens without an entry are
+/// only constrained by `allowed_calls`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionPolicy {
+    pub allowed_calls: BTreeMap<FieldElement, Vec<FieldElement>>,
+    pub spend_limits: BTreeMap<FieldElement, u128>,
+    /// Unix seconds; 0 = valid immediately.
+    pub valid_after: u64,
+    /// Unix seconds; 0 = no expiry.
+    pub valid_until: u64,
+}
+
+impl SessionPolicy {
+    /// Fail unless `now` lies inside `[valid_after, valid_until)`.
+    pub fn check_window(&self, now: u64) -> Result<(), PolicyViolation> {
+        if now < self.valid_after {
+            return Err(PolicyViolation::NotYetValid { valid_after: self.valid_after });
+        }
+        if self.valid_until != 0 && now >= self.valid_until {
+            return Err(PolicyViolation::Expired { expires_at: self.valid_until });
+        }
+        Ok(())
+    }
+
+    /// Check a multicall against the policy given what has already been spent.
+    /// Returns the per-token totals after these calls, to be recorded once signed.
+    pub fn check_calls(
+        &self,
+        calls: &[Call],
+        spent: &BTreeMap<FieldElement, u128>,
+        now: u64,
+    ) -> Result<BTreeMap<FieldElement, u128>, PolicyViolation> {
+        self.check_window(now)?;
+
+        let mut totals = spent.clone();
+        for call in calls {
+            let Some(selectors) = self.allowed_calls.get(&call.to) else {
+                return Err(PolicyViolation::ContractNotAllowed { contract: call.to });
+            };
+            if !selectors.contains(&call.selector) {
+                return Err(PolicyViolation::SelectorNotAllowed { contract: call.to, selector: call.selector });
+            }
+
+            let Some(limit) = self.spend_limits.get(&call.to) else { continue };
+            let Some(requested) = spend_amount(call)? else { continue };
+            let total = totals.entry(call.to).or_default();
+            let remaining = limit.saturating_sub(*total);
+            if requested > remaining {
+                return Err(PolicyViolation::SpendLimitExceeded { token: call.to, requested, remaining });
+            }
+            *total += requested;
+        }
+        Ok(totals)
+    }
+}
+
+/// ERC-20 amount moved by `call`, or `None` if it is not a spending entry point.
+/// Amounts that do not fit in a u128 saturate, so they exceed any finite limit.
+fn spend_amount(call: &Call) -> Result<Option<u128>, PolicyViolation> {
+    let Some(&(_, index)) = SPEND_ENTRY_POINTS.iter()
+        .find(|(name, _)| get_selector_from_name(name).ok() == Some(call.selector))
+    else {
+        return Ok(None);
+    };
+
+    let malformed = || PolicyViolation::MalformedCalldata { contract: call.to, selector: call.selector };
+    let low = call.calldata.get(index).ok_or_else(malformed)?;
+    let high = call.calldata.get(index + 1).ok_or_else(malformed)?;
+    if *high != FieldElement::ZERO {
+        return Ok(Some(u128::MAX));
+    }
+    Ok(Some(u128::try_from(*low).unwrap_or(u128::MAX)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn felt(hex: &str) -> FieldElement {
+        FieldElement::from_hex_be(hex).unwrap()
+    }
+
+    fn selector(name: &str) -> FieldElement {
+        get_selector_from_name(name).unwrap()
+    }
+
+    fn token() -> FieldElement {
+        felt("0x70")
+    }
+
+    fn dispatcher() -> FieldElement {
+        felt("0xd1")
+    }
+
+    fn transfer(to: FieldElement, amount: u64) -> Call {
+        Call { to, selector: selector("transfer"), calldata: vec![felt("0x456"), amount.into(), FieldElement::ZERO] }
+    }
+
+    /// `transfer` on the token (capped at 100) and `run_process` on the dispatcher,
+    /// valid during [1000, 2000).
+    fn policy() -> SessionPolicy {
+        SessionPolicy {
+            allowed_calls: BTreeMap::from([
+                (token(), vec![selector("transfer")]),
+                (dispatcher(), vec![selector("run_process")]),
+            ]),
+            spend_limits: BTreeMap::from([(token(), 100)]),
+            valid_after: 1000,
+            valid_until: 2000,
+        }
+    }
+
+    #[test]
+    fn allowed_pairs_pass_and_accumulate_spend() {
+        let run = Call { to: dispatcher(), selector: selector("run_process"), calldata: vec![] };
+        let totals = policy().check_calls(&[transfer(token(), 60), run], &BTreeMap::new(), 1500).unwrap();
+        assert_eq!(totals[&token()], 60);
+    }
+
+    #[test]
+    fn selector_is_only_allowed_on_its_own_contract() {
+        let err = policy().check_calls(&[transfer(dispatcher(), 1)], &BTreeMap::new(), 1500).unwrap_err();
+        assert_eq!(err, PolicyViolation::SelectorNotAllowed { contract: dispatcher(), selector: selector("transfer") });
+
+        let run_on_token = Call { to: token(), selector: selector("run_process"), calldata: vec![] };
+        assert!(matches!(
+            policy().check_calls(&[run_on_token], &BTreeMap::new(), 1500),
+            Err(PolicyViolation::SelectorNotAllowed { .. })
+        ));
+        assert_eq!(
+            policy().check_calls(&[transfer(felt("0x99"), 1)], &BTreeMap::new(), 1500).unwrap_err(),
+            PolicyViolation::ContractNotAllowed { contract: felt("0x99") }
+        );
+    }
+
+    #[test]
+    fn spend_over_the_cap_is_refused() {
+        let spent = BTreeMap::from([(token(), 60)]);
+        assert_eq!(
+            policy().check_calls(&[transfer(token(), 41)], &spent, 1500).unwrap_err(),
+            PolicyViolation::SpendLimitExceeded { token: token(), requested: 41, remaining: 40 }
+        );
+        // Two calls in one multicall add up.
+        assert!(policy().check_calls(&[transfer(token(), 30), transfer(token(), 30)], &spent, 1500).is_err());
+        assert!(policy().check_calls(&[transfer(token(), 40)], &spent, 1500).is_ok());
+
+        let mut huge = transfer(token(), 1);
+        huge.calldata[2] = FieldElement::ONE;
+        assert!(matches!(
+            policy().check_calls(&[huge], &BTreeMap::new(), 1500),
+            Err(PolicyViolation::SpendLimitExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn window_is_enforced() {
+        let calls = [transfer(token(), 1)];
+        assert_eq!(
+            policy().check_calls(&calls, &BTreeMap::new(), 999).unwrap_err(),
+            PolicyViolation::NotYetValid { valid_after: 1000 }
+        );
+        assert_eq!(
+            policy().check_calls(&calls, &BTreeMap::new(), 2000).unwrap_err(),
+            PolicyViolation::Expired { expires_at: 2000 }
+        );
+    }
+}
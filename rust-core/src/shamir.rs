@@ -0,0 +1,251 @@
+use aes_gcm::aead::OsRng;
+use anyhow::{Context, Result};
+use rand::RngCore;
+use std::collections::HashSet;
+use zeroize::Zeroizing;
+
+/// Text prefix of an encoded share (format version 1).
+const SHARE_PREFIX: &str = "SPRV1";
+
+/// One Shamir share over GF(256), applied byte-wise to the secret.
+/// `set_id` ties together shares produced by the same split so they can't be mixed.
+pub struct Share {
+    pub set_id: [u8; 4],
+    pub threshold: u8,
+    pub index: u8,
+    data: Zeroizing<Vec<u8>>,
+}
+
+impl Share {
+    /// Encode as `SPRV1-<set>-<threshold>-<index>-<data hex>-<checksum>`, where the
+    /// checksum is the first 4 bytes of a blake3 hash over everything before it.
+    pub fn encode(&self) -> String {
+        let body = format!(
+            "{}-{}-{}-{}-{}",
+            SHARE_PREFIX,
+            hex::encode(self.set_id),
+            self.threshold,
+            self.index,
+            hex::encode(self.data.as_slice())
+        );
+        let checksum = share_checksum(&body);
+        format!("{}-{}", body, checksum)
+    }
+
+    pub fn decode(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let (body, checksum) = text.rsplit_once('-').context("Malformed share")?;
+        if share_checksum(body) != checksum.to_ascii_lowercase() {
+            return Err(anyhow::anyhow!("Share checksum mismatch (typo or truncated share?)"));
+        }
+
+        let parts: Vec<&str> = body.split('-').collect();
+        if parts.len() != 5 || parts[0] != SHARE_PREFIX {
+            return Err(anyhow::anyhow!("Unsupported share format"));
+        }
+        let set_id: [u8; 4] = hex::decode(parts[1]).ok()
+            .and_then(|b| b.try_into().ok())
+            .context("Invalid share set id")?;
+        let threshold: u8 = parts[2].parse().context("Invalid share threshold")?;
+        let index: u8 = parts[3].parse().context("Invalid share index")?;
+        let data = Zeroizing::new(hex::decode(parts[4]).context("Invalid share data")?);
+
+        if threshold < 2 || index == 0 {
+            return Err(anyhow::anyhow!("Invalid share parameters"));
+        }
+        Ok(Share { set_id, threshold, index, data })
+    }
+}
+
+fn share_checksum(body: &str) -> String {
+    hex::encode(&blake3::hash(body.as_bytes()).as_bytes()[..4])
+}
+
+/// Split `secret` into `shares` shares, any `threshold` of which rebuild it.
+pub fn split(secret: &[u8], threshold: u8, shares: u8) -> Result<Vec<Share>> {
+    if threshold < 2 {
+        return Err(anyhow::anyhow!("Threshold must be at least 2"));
+    }
+    if shares < threshold {
+        return Err(anyhow::anyhow!("Share count ({}) must be >= threshold ({})", shares, threshold));
+    }
+    if secret.is_empty() {
+        return Err(anyhow::anyhow!("Secret must not be empty"));
+    }
+
+    let mut set_id = [0u8; 4];
+    OsRng.fill_bytes(&mut set_id);
+
+    let mut out: Vec<Share> = (1..=shares)
+        .map(|index| Share { set_id, threshold, index, data: Zeroizing::new(Vec::with_capacity(secret.len())) })
+        .collect();
+
+    // One random polynomial of degree threshold-1 per secret byte, constant term = byte.
+    let mut coefficients = Zeroizing::new(vec![0u8; threshold as usize]);
+    for &byte in secret {
+        coefficients[0] = byte;
+        OsRng.fill_bytes(&mut coefficients[1..]);
+        for share in out.iter_mut() {
+            let y = eval_polynomial(&coefficients, share.index);
+            share.data.push(y);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Rebuild the secret from at least `threshold` shares of the same split.
+pub fn combine(shares: &[Share]) -> Result<Zeroizing<Vec<u8>>> {
+    let first = shares.first().context("No shares provided")?;
+    let threshold = first.threshold as usize;
+
+    let mut seen = HashSet::new();
+    for share in shares {
+        if share.set_id != first.set_id || share.threshold != first.threshold {
+            return Err(anyhow::anyhow!("Shares come from different splits"));
+        }
+        if share.data.len() != first.data.len() {
+            return Err(anyhow::anyhow!("Shares have inconsistent lengths"));
+        }
+        if !seen.insert(share.index) {
+            return Err(anyhow::anyhow!("Duplicate share index {}", share.index));
+        }
+    }
+    if shares.len() < threshold {
+        return Err(anyhow::anyhow!("Need {} shares, got {}", threshold, shares.len()));
+    }
+
+    let used = &shares[..threshold];
+    let mut secret = Zeroizing::new(Vec::with_capacity(first.data.len()));
+    for pos in 0..first.data.len() {
+        // Lagrange interpolation at x = 0.
+        let mut value = 0u8;
+        for (i, share_i) in used.iter().enumerate() {
+            let mut basis = 1u8;
+            for (j, share_j) in used.iter().enumerate() {
+                if i != j {
+                    let num = share_j.index;
+                    let den = share_j.index ^ share_i.index;
+                    basis = gf_mul(basis, gf_div(num, den));
+                }
+            }
+            value ^= gf_mul(share_i.data[pos], basis);
+        }
+        secret.push(value);
+    }
+    Ok(secret)
+}
+
+/// Split a vault password into encoded text shares.
+pub fn split_password(password: &str, threshold: u8, shares: u8) -> Result<Vec<String>> {
+    Ok(split(password.as_bytes(), threshold, shares)?
+        .iter()
+        .map(Share::encode)
+        .collect())
+}
+
+/// Rebuild a vault password from encoded text shares.
+pub fn recover_password<S: AsRef<str>>(encoded: &[S]) -> Result<Zeroizing<String>> {
+    let shares = encoded.iter()
+        .map(|s| Share::decode(s.as_ref()))
+        .collect::<Result<Vec<_>>>()?;
+    let secret = combine(&shares)?;
+    let password = std::str::from_utf8(&secret).context("Recovered password is not valid UTF-8")?;
+    Ok(Zeroizing::new(password.to_string()))
+}
+
+fn eval_polynomial(coefficients: &[u8], x: u8) -> u8 {
+    // Horner's rule, highest degree first.
+    coefficients.iter().rev().fold(0u8, |acc, &c| gf_mul(acc, x) ^ c)
+}
+
+/// Multiplication in GF(2^8) with the AES polynomial x^8 + x^4 + x^3 + x + 1.
+/// Branch-free on the operand bits so timing does not depend on share data.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (0x1b & carry);
+        b >>= 1;
+    }
+    product
+}
+
+fn gf_inv(a: u8) -> u8 {
+    // a^254 = a^-1 in GF(2^8).
+    let mut result = 1u8;
+    let mut base = a;
+    let mut exp = 254u8;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = gf_mul(result, base);
+        }
+        base = gf_mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+fn gf_div(a: u8, b: u8) -> u8 {
+    gf_mul(a, gf_inv(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"correct horse battery staple";
+
+    #[test]
+    fn gf_mul_matches_aes_spec() {
+        // FIPS-197 section 4.2 example.
+        assert_eq!(gf_mul(0x57, 0x83), 0xc1);
+        assert_eq!(gf_mul(0x57, 0x13), 0xfe);
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_inv(a)), 1);
+        }
+    }
+
+    #[test]
+    fn any_threshold_subset_recovers_secret() {
+        let shares = split(SECRET, 3, 5).unwrap();
+        for a in 0..5 {
+            for b in a + 1..5 {
+                for c in b + 1..5 {
+                    let subset: Vec<Share> = [a, b, c].iter()
+                        .map(|&i| Share::decode(&shares[i].encode()).unwrap())
+                        .collect();
+                    assert_eq!(combine(&subset).unwrap().as_slice(), SECRET);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn too_few_or_mixed_shares_are_rejected() {
+        let shares = split(SECRET, 3, 5).unwrap();
+        assert!(combine(&shares[..2]).is_err());
+
+        let mut other = split(SECRET, 3, 5).unwrap();
+        let mixed = vec![other.remove(0), Share::decode(&shares[1].encode()).unwrap(), other.remove(1)];
+        assert!(combine(&mixed).is_err());
+
+        let duplicate: Vec<Share> = [0, 0, 1].iter()
+            .map(|&i| Share::decode(&shares[i].encode()).unwrap())
+            .collect();
+        assert!(combine(&duplicate).is_err());
+    }
+
+    #[test]
+    fn password_round_trip_and_typo_detection() {
+        let encoded = split_password("hunter2 but longer", 2, 3).unwrap();
+        assert_eq!(recover_password(&encoded[1..]).unwrap().as_str(), "hunter2 but longer");
+
+        let mut typo = encoded[0].clone();
+        let flipped = if typo.ends_with('0') { '1' } else { '0' };
+        typo.pop();
+        typo.push(flipped);
+        assert!(Share::decode(&typo).is_err());
+    }
+}
@@ -1,12 +1,67 @@
-use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet::providers::jsonrpc::{HttpTransportError, JsonRpcClientError};
+use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, ProviderError};
 use url::Url;
 use anyhow::{Context, Result};
 use crate::rate_limiter::ApiRateLimiter;
+use crate::nonce_manager::{NonceManager, Reservation};
+use crate::provider_health::{HealthTracker, ProviderHealth, MAX_BLOCK_LAG};
+use crate::quorum::{self, QuorumAnswer, QuorumError, QuorumRead, QuorumValue};
+use crate::tokens::{self, Token, TokenBalance};
+use crate::batch_query::{AsteroidOwnership, BatchQueryResult, ASTEROID_CONTRACT_ENV, MAX_BATCH_CALLS};
+use crate::receipt::{TxReceipt, WatchOptions, WatchTimeout};
+use crate::state_file::unix_now;
+use crate::transaction::{FeeEstimate, InvokeSigner, InvokeV3, ResourceBound, Simulation};
+use futures::future::join_all;
+use serde_json::{json, Value};
+use starknet::accounts::Call;
+use starknet::core::types::FieldElement;
+use starknet::core::utils::cairo_short_string_to_felt;
 use std::env;
+use std::future::Future;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::OnceCell;
+
+/// Pins the network every provider must be on (`SN_MAIN`, `SN_SEPOLIA` or hex).
+/// Without it, the chain id most providers report wins.
+pub const CHAIN_ID_ENV: &str = "STARKNET_CHAIN_ID";
+
+/// `TXN_HASH_NOT_FOUND`: the node has not seen the transaction (yet).
+const TXN_HASH_NOT_FOUND: i64 = 29;
+/// A submitted transaction the node still does not know after this long was dropped,
+/// and its nonce can be reused.
+const DROPPED_AFTER_SECS: u64 = 600;
+/// Bounds on every RPC request. A hung endpoint then fails as a [`ProviderFault`] and
+/// is benched instead of stalling the pulse (and outliving a nonce reservation).
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The endpoint itself failed (unreachable, HTTP error, garbage response, rate limit),
+/// as opposed to answering with a Starknet error. Only these trigger failover.
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct ProviderFault(String);
+
+/// JSON-RPC error object returned by a node.
+#[derive(Debug, Error)]
+#[error("{method} failed ({code}): {message}{}", data.as_ref().map(|d| format!(": {}", d)).unwrap_or_default())]
+pub struct RpcError {
+    pub method: String,
+    pub code: i64,
+    pub message: String,
+    pub data: Option<Value>,
+}
 
 pub struct StarknetClient {
     providers: Vec<JsonRpcClient<HttpTransport>>,
+    /// Same endpoints as `providers`, for methods spoken as raw JSON-RPC.
+    rpc_urls: Vec<Url>,
+    http: reqwest::Client,
+    nonces: NonceManager,
+    health: HealthTracker,
+    pinned_chain_id: Option<FieldElement>,
+    expected_chain_id: OnceCell<FieldElement>,
     current_index: AtomicUsize,
     limiter: ApiRateLimiter,
 }
@@ -27,16 +82,32 @@ impl StarknetClient {
             url_strings = Self::detect_rpc_urls()?;
         }
 
+        let http = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("Failed to build HTTP client")?;
+
         let mut providers = Vec::new();
+        let mut rpc_urls = Vec::new();
         for url_str in url_strings {
             let url = Url::parse(&url_str).context(format!("Invalid RPC URL: {}", url_str))?;
-            providers.push(JsonRpcClient::new(HttpTransport::new(url)));
+            providers.push(JsonRpcClient::new(HttpTransport::new_with_client(url.clone(), http.clone())));
+            rpc_urls.push(url);
         }
 
         if providers.is_empty() {
              return Err(anyhow::anyhow!("No valid RPC providers available."));
         }
 
+        let pinned_chain_id = match env::var(CHAIN_ID_ENV) {
+            Ok(raw) if raw.trim().starts_with("0x") => Some(FieldElement::from_hex_be(raw.trim())
+                .map_err(|_| anyhow::anyhow!("Invalid {}: {}", CHAIN_ID_ENV, raw))?),
+            Ok(raw) if !raw.trim().is_empty() => Some(cairo_short_string_to_felt(raw.trim())
+                .context(format!("Invalid {}: {}", CHAIN_ID_ENV, raw))?),
+            _ => None,
+        };
+
         // Default to safe limit: 5 requests per second (typical free tier)
         // Note: This limit is global for the client struct, effectively limiting total throughput 
         // regardless of which provider is used next.
@@ -44,6 +115,12 @@ impl StarknetClient {
 
         Ok(StarknetClient { 
             providers, 
+            health: HealthTracker::new(&rpc_urls),
+            rpc_urls,
+            http,
+            nonces: NonceManager::from_env()?,
+            pinned_chain_id,
+            expected_chain_id: OnceCell::new(),
             current_index: AtomicUsize::new(0),
             limiter 
         })
@@ -80,22 +157,562 @@ impl StarknetClient {
         }
     }
 
-    fn next_provider(&self) -> &JsonRpcClient<HttpTransport> {
-        let idx = self.current_index.fetch_add(1, Ordering::Relaxed);
-        &self.providers[idx % self.providers.len()]
+    /// Current health of every provider, in configuration order.
+    pub fn provider_health(&self) -> Vec<ProviderHealth> {
+        self.health.snapshot()
     }
 
-    pub async fn get_network_status(&self) -> Result<(u64, u128)> {
+    /// Provider for the next request: round-robin over healthy providers on the
+    /// expected chain, skipping `exclude`. Benched providers are only picked when no
+    /// healthy one is left.
+    async fn pick(&self, exclude: &[usize]) -> Result<usize> {
+        let expected = self.expected_chain_id().await.ok();
+        let start = self.current_index.fetch_add(1, Ordering::Relaxed);
+        for idx in self.health.candidates(start, exclude) {
+            if self.on_expected_chain(idx, expected).await {
+                return Ok(idx);
+            }
+        }
+        Err(ProviderFault("No usable RPC provider left".to_string()).into())
+    }
+
+    /// Check provider `idx` against the expected chain id the first time it is used.
+    /// False for a wrong chain, or when the check itself failed.
+    async fn on_expected_chain(&self, idx: usize, expected: Option<FieldElement>) -> bool {
+        let Some(expected) = expected else { return true };
+        if let Some(ok) = self.health.chain_id_ok(idx) {
+            return ok;
+        }
+        let Ok(chain_id) = self.rpc_felt(idx, "starknet_chainId", json!([])).await else { return false };
+        self.health.record_chain_id(idx, chain_id, expected);
+        if chain_id != expected {
+            log::warn!("Skipping RPC provider {}: wrong chain", self.health.endpoint(idx));
+        }
+        chain_id == expected
+    }
+
+    /// `$STARKNET_CHAIN_ID`, or the chain id reported by most providers (ties go to the
+    /// first configured). Settled once; retried later if no provider answered.
+    async fn expected_chain_id(&self) -> Result<FieldElement> {
+        self.expected_chain_id.get_or_try_init(|| async {
+            if let Some(pinned) = self.pinned_chain_id {
+                return Ok(pinned);
+            }
+
+            // Asked concurrently, so one slow provider costs at most one timeout.
+            let replies = join_all((0..self.providers.len()).map(|idx| async move {
+                self.rpc_felt(idx, "starknet_chainId", json!([])).await.ok().map(|chain_id| (idx, chain_id))
+            })).await;
+            let answers: Vec<(usize, FieldElement)> = replies.into_iter().flatten().collect();
+            let expected = answers.iter()
+                .map(|(_, id)| *id)
+                .max_by_key(|id| {
+                    let votes = answers.iter().filter(|(_, other)| other == id).count();
+                    let first = answers.iter().position(|(_, other)| other == id).unwrap_or_default();
+                    (votes, std::cmp::Reverse(first))
+                })
+                .context("No RPC provider reported a chain id")?;
+            for (idx, chain_id) in answers {
+                self.health.record_chain_id(idx, chain_id, expected);
+            }
+            Ok(expected)
+        }).await.copied()
+    }
+
+    /// Run `request` on provider after provider until one does not fail with a
+    /// [`ProviderFault`]. Starknet errors (bad address, reverted call) are answers
+    /// and returned as-is.
+    async fn with_failover<T, F, Fut>(&self, request: F) -> Result<T>
+    where
+        F: Fn(usize) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut tried = Vec::new();
+        loop {
+            let idx = self.pick(&tried).await?;
+            tried.push(idx);
+            match request(idx).await {
+                Err(e) if is_provider_fault(&e) && tried.len() < self.providers.len() => {
+                    log::warn!("RPC provider {} failed, failing over: {:#}", self.health.endpoint(idx), e);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Send `method` to `quorum` providers at the same block and return the answer
+    /// only if all of them agree once `decode`d, so encodings of the same value match. The block is the lowest head among up-to-date
+    /// providers, so every voter has it; providers more than [`MAX_BLOCK_LAG`] blocks
+    /// behind the highest head are flagged and left out. A voter that fails is
+    /// replaced by the next up-to-date provider.
+    async fn quorum_request<T: QuorumValue>(
+        &self,
+        method: &str,
+        quorum: usize,
+        params: impl Fn(Value) -> Value,
+        decode: impl Fn(&Value) -> Result<T>,
+    ) -> Result<QuorumRead<T>> {
+        if quorum == 0 {
+            anyhow::bail!("Quorum size must be at least 1");
+        }
+        let expected = self.expected_chain_id().await.ok();
+        let start = self.current_index.fetch_add(1, Ordering::Relaxed);
+        let candidates = self.health.candidates(start, &[]);
+
+        let heads = join_all(candidates.iter().map(|&idx| async move {
+            if !self.on_expected_chain(idx, expected).await {
+                return None;
+            }
+            let head = self.rpc_request(idx, "starknet_blockNumber", json!([])).await.ok()?.as_u64()?;
+            self.health.record_block(idx, head);
+            Some((idx, head))
+        })).await;
+        let heads: Vec<(usize, u64)> = heads.into_iter().flatten().collect();
+
+        let best = heads.iter().map(|(_, head)| *head).max().unwrap_or_default();
+        let (lagging, fresh): (Vec<_>, Vec<_>) = heads.into_iter().partition(|(_, head)| best - head > MAX_BLOCK_LAG);
+        let lagging: Vec<String> = lagging.iter().map(|(idx, _)| self.health.endpoint(*idx)).collect();
+        if !lagging.is_empty() {
+            log::warn!("RPC providers lagging behind block {}: {}", best, lagging.join(", "));
+        }
+        let not_enough = |available| QuorumError::NotEnoughProviders {
+            method: method.to_string(),
+            needed: quorum,
+            available,
+            lagging: lagging.clone(),
+        };
+        if fresh.len() < quorum {
+            return Err(not_enough(fresh.len()).into());
+        }
+        let block_number = fresh.iter().map(|(_, head)| *head).min().unwrap_or_default();
+        let block_id = json!({ "block_number": block_number });
+
+        let mut spares = fresh.iter().map(|(idx, _)| *idx);
+        let mut voters: Vec<usize> = spares.by_ref().take(quorum).collect();
+        let mut results: Vec<(usize, Result<T>)> = Vec::new();
+        while !voters.is_empty() {
+            let round = join_all(voters.drain(..).map(|idx| {
+                let request = self.rpc_request(idx, method, params(block_id.clone()));
+                async move { (idx, request.await) }
+            })).await;
+            for (idx, result) in round {
+                match result {
+                    Err(e) if is_provider_fault(&e) => voters.extend(spares.next()),
+                    result => results.push((idx, result.and_then(|value| decode(&value)))),
+                }
+            }
+        }
+        if results.len() < quorum {
+            return Err(not_enough(results.len()).into());
+        }
+
+        // Starknet errors are answers too (e.g. every node says the contract does not
+        // exist); only their code is compared, the wording differs between nodes.
+        let answers: Vec<QuorumAnswer> = results.iter()
+            .map(|(idx, result)| QuorumAnswer {
+                provider: *idx,
+                endpoint: self.health.endpoint(*idx),
+                answer: match result {
+                    Ok(value) => value.canonical(),
+                    Err(e) => match e.downcast_ref::<RpcError>() {
+                        Some(rpc) => format!("error {}", rpc.code),
+                        None => format!("error: {:#}", e),
+                    },
+                },
+            })
+            .collect();
+        if let Some(dissenters) = quorum::dissenters(&answers) {
+            return Err(QuorumError::Disagreement {
+                method: method.to_string(),
+                block_number,
+                answers,
+                dissenters,
+            }.into());
+        }
+
+        let providers = answers.into_iter().map(|a| a.endpoint).collect();
+        let (_, agreed) = results.swap_remove(0);
+        Ok(QuorumRead { value: agreed?, block_number, providers, lagging })
+    }
+
+    /// Nonce of `account`, agreed on by `quorum` providers.
+    pub async fn quorum_nonce(&self, account: FieldElement, quorum: usize) -> Result<QuorumRead<FieldElement>> {
+        self.quorum_request("starknet_getNonce", quorum, |block_id| json!({
+            "block_id": block_id,
+            "contract_address": format!("{:#x}", account),
+        }), |nonce| felt_from_json("starknet_getNonce", nonce)).await
+    }
+
+    /// Result of calling `selector` on `contract`, agreed on by `quorum` providers:
+    /// balances, crew state or anything else worth double-checking before spending.
+    pub async fn quorum_call(
+        &self,
+        contract: FieldElement,
+        selector: FieldElement,
+        calldata: &[FieldElement],
+        quorum: usize,
+    ) -> Result<QuorumRead<Vec<FieldElement>>> {
+        self.quorum_request("starknet_call", quorum, |block_id| json!({
+            "request": {
+                "contract_address": format!("{:#x}", contract),
+                "entry_point_selector": format!("{:#x}", selector),
+                "calldata": calldata.iter().map(|d| format!("{:#x}", d)).collect::<Vec<_>>(),
+            },
+            "block_id": block_id,
+        }), |result| felts_from_json("starknet_call", result)).await
+    }
+
+    /// Rate-limit, time and score one request to provider `idx`.
+    async fn tracked<T>(&self, idx: usize, request: impl Future<Output = Result<T>>) -> Result<T> {
         self.limiter.check().await;
+        let started = Instant::now();
+        let result = request.await;
+        match &result {
+            Err(e) if is_provider_fault(e) => self.health.record_failure(idx, e),
+            _ => self.health.record_success(idx, started.elapsed()),
+        }
+        result
+    }
+
+    /// Raw JSON-RPC request, for methods whose shape changed after the RPC version
+    /// `starknet-rs` 0.9 speaks (v3 fees, `l1_data_gas`, spec 0.7+ estimates).
+    async fn rpc_request(&self, idx: usize, method: &str, params: Value) -> Result<Value> {
+        self.tracked(idx, self.rpc_exchange(&self.rpc_urls[idx], method, params)).await
+    }
+
+    async fn rpc_exchange(&self, url: &Url, method: &str, params: Value) -> Result<Value> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self.http.post(url.clone()).json(&body).send().await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| ProviderFault(format!("{} request failed: {}", method, e)))?
+            .json().await
+            .map_err(|e| ProviderFault(format!("{} returned an invalid response: {}", method, e)))?;
+        rpc_result(method, &response)
+    }
+
+    /// Send one body of [`batch_bodies`] to provider `idx`. `None` when the provider
+    /// does not take batches.
+    async fn rpc_batch(&self, idx: usize, body: &[Value]) -> Result<Option<Vec<Result<Value>>>> {
+        self.tracked(idx, async {
+            let response = self.http.post(self.rpc_urls[idx].clone()).json(body).send().await
+                .map_err(|e| ProviderFault(format!("starknet_call batch request failed: {}", e)))?;
+            if response.status().is_server_error() {
+                return Err(ProviderFault(format!("starknet_call batch request failed: HTTP {}", response.status())).into());
+            }
+            // Providers without batch support answer 4xx, a single error object or
+            // something that is not an array.
+            let Ok(Value::Array(responses)) = response.json::<Value>().await else { return Ok(None) };
+            Ok(batch_results(body.len(), &responses))
+        }).await
+    }
+
+    /// Results of `calls` on provider `idx`: JSON-RPC batches of [`MAX_BATCH_CALLS`]
+    /// where the provider takes them, otherwise one request per call, run
+    /// concurrently and paced by the rate limiter. Each call keeps its own Starknet
+    /// error; a provider fault fails the whole read. The flag tells whether batches
+    /// were used.
+    async fn call_many(&self, idx: usize, calls: &[Value]) -> Result<(Vec<Result<Value>>, bool)> {
+        if self.health.batch_supported(idx) != Some(false) {
+            let mut results = Vec::with_capacity(calls.len());
+            let mut supported = true;
+            for body in batch_bodies(calls) {
+                self.limiter.check().await;
+                match self.rpc_batch(idx, &body).await? {
+                    Some(chunk_results) => results.extend(chunk_results),
+                    None => {
+                        supported = false;
+                        break;
+                    }
+                }
+            }
+            self.health.record_batch_support(idx, supported);
+            if supported {
+                return Ok((results, true));
+            }
+            log::warn!("RPC provider {} does not take JSON-RPC batches; sending calls one by one", self.health.endpoint(idx));
+        }
+
+        let results = join_all(calls.iter().map(|params| self.rpc_request(idx, "starknet_call", params.clone()))).await;
+        if let Some(fault) = results.iter().position(|r| r.as_ref().is_err_and(is_provider_fault)) {
+            return Err(results.into_iter().nth(fault).and_then(Result::err).unwrap_or_else(|| anyhow::anyhow!("starknet_call failed")));
+        }
+        Ok((results, false))
+    }
+
+    async fn rpc_felt(&self, idx: usize, method: &str, params: Value) -> Result<FieldElement> {
+        let result = self.rpc_request(idx, method, params).await?;
+        felt_from_json(method, &result)
+    }
+
+    /// Whether the node takes `l1_data_gas` resource bounds (RPC spec 0.8 and later).
+    async fn supports_l1_data_gas(&self, idx: usize) -> Result<bool> {
+        let version = self.rpc_request(idx, "starknet_specVersion", json!([])).await?;
+        let version = version.as_str().context("starknet_specVersion did not return a string")?;
+        let mut parts = version.split('.').map(|p| p.parse::<u32>().unwrap_or_default());
+        let major = parts.next().unwrap_or_default();
+        let minor = parts.next().unwrap_or_default();
+        Ok((major, minor) >= (0, 8))
+    }
+
+    /// Unsigned, unpriced INVOKE v3 of `calls` at the account's current nonce. The
+    /// flag tells whether the node expects per-resource (`l1_data_gas`) bounds.
+    async fn prepare_invoke(&self, idx: usize, account: FieldElement, calls: &[Call]) -> Result<(InvokeV3, bool)> {
+        let chain_id = self.rpc_felt(idx, "starknet_chainId", json!([])).await?;
+        let nonce = self.chain_nonce(idx, account).await?;
+        let per_resource = self.supports_l1_data_gas(idx).await?;
+
+        let mut tx = InvokeV3::new(account, calls, nonce, chain_id);
+        if per_resource {
+            // RPC 0.8 requires all three bounds even on the unpriced estimate request.
+            tx.resource_bounds.l1_data_gas = Some(ResourceBound::default());
+        }
+        Ok((tx, per_resource))
+    }
+
+    async fn chain_nonce(&self, idx: usize, account: FieldElement) -> Result<FieldElement> {
+        self.rpc_felt(idx, "starknet_getNonce", json!({
+            "block_id": "latest",
+            "contract_address": format!("{:#x}", account),
+        })).await
+    }
+
+    /// Release reserved nonces whose submitted transaction was rejected, or that the
+    /// node has not heard of [`DROPPED_AFTER_SECS`] after submission.
+    async fn release_dropped_nonces(&self, idx: usize, account: FieldElement, chain_nonce: u64) -> Result<()> {
+        let now = unix_now()?;
+        for reservation in self.nonces.reservations(account)? {
+            let (Some(tx_hash), Some(submitted_at)) = (&reservation.tx_hash, reservation.submitted_at) else { continue };
+            if reservation.nonce < chain_nonce {
+                continue;
+            }
+
+            let status = self.rpc_request(idx, "starknet_getTransactionStatus", json!({ "transaction_hash": tx_hash })).await;
+            let dropped = match status {
+                Ok(status) => status.get("finality_status").and_then(Value::as_str) == Some("REJECTED"),
+                Err(e) if e.downcast_ref::<RpcError>().is_some_and(|rpc| rpc.code == TXN_HASH_NOT_FOUND) => {
+                    now.saturating_sub(submitted_at) >= DROPPED_AFTER_SECS
+                }
+                Err(e) => return Err(e),
+            };
+            if dropped {
+                log::warn!("Releasing nonce {} of dropped transaction {}", reservation.nonce, tx_hash);
+                self.nonces.release(account, reservation.nonce)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Local reservations of `account` that have not landed yet, and the nonces
+    /// missing between the chain nonce and the highest reservation.
+    pub async fn nonce_report(&self, account: FieldElement) -> Result<(u64, Vec<Reservation>, Vec<u64>)> {
+        let chain_nonce = self.with_failover(|idx| self.chain_nonce(idx, account)).await?;
+        let chain_nonce = nonce_to_u64(chain_nonce)?;
+        let pending = self.nonces.reservations(account)?
+            .into_iter()
+            .filter(|r| r.nonce >= chain_nonce)
+            .collect();
+        Ok((chain_nonce, pending, self.nonces.gaps(account, chain_nonce)?))
+    }
+
+    /// `tx` as an unsigned query-only transaction, for estimates and simulations with
+    /// validation skipped.
+    fn query_json(tx: &InvokeV3) -> Value {
+        let mut query = tx.clone();
+        query.is_query = true;
+        query.to_rpc_json(&[])
+    }
+
+    async fn estimate_invoke_fee(&self, idx: usize, tx: &InvokeV3) -> Result<FeeEstimate> {
+        let params = json!({
+            "request": [Self::query_json(tx)],
+            "simulation_flags": ["SKIP_VALIDATE"],
+            "block_id": "latest",
+        });
+        let result = self.rpc_request(idx, "starknet_estimateFee", params).await?;
+        let estimate = result.as_array().and_then(|r| r.first())
+            .context("starknet_estimateFee returned no estimate")?;
+        FeeEstimate::from_rpc_json(estimate)
+    }
+
+    /// Fee `calls` from `account` would cost right now, split into L1 gas, L1 data gas
+    /// and L2 gas. Fails with the node's execution error if the calls would revert.
+    pub async fn estimate_fee(&self, account: FieldElement, calls: &[Call]) -> Result<FeeEstimate> {
+        self.with_failover(|idx| async move {
+            let (tx, _) = self.prepare_invoke(idx, account, calls).await?;
+            self.estimate_invoke_fee(idx, &tx).await.context("Failed to estimate fee")
+        }).await
+    }
+
+    /// Dry-run `calls` from `account` without a signature. Unlike [`Self::estimate_fee`],
+    /// a reverting execution is not an error: its fee and revert reason are returned,
+    /// so callers can reject the transaction before signing it.
+    pub async fn simulate(&self, account: FieldElement, calls: &[Call]) -> Result<Simulation> {
+        self.with_failover(|idx| async move {
+            let (tx, _) = self.prepare_invoke(idx, account, calls).await?;
+            let params = json!({
+                "block_id": "latest",
+                "transactions": [Self::query_json(&tx)],
+                // The query carries no resource bounds, so charging its fee would fail
+                // before execution and hide the real outcome.
+                "simulation_flags": ["SKIP_VALIDATE", "SKIP_FEE_CHARGE"],
+            });
+            let result = self.rpc_request(idx, "starknet_simulateTransactions", params).await
+                .context("Failed to simulate transaction")?;
+            let simulated = result.as_array().and_then(|r| r.first())
+                .context("starknet_simulateTransactions returned no result")?;
+            Simulation::from_rpc_json(simulated)
+        }).await
+    }
+
+    /// Build, sign and submit an INVOKE v3 transaction executing `calls` from
+    /// `account`, paying in STRK with resource bounds of `fee_multiplier` times the
+    /// estimate. Once the nonce is read, every request goes to that provider so nonce,
+    /// estimate and submission see the same chain state. The nonce comes from the
+    /// shared [`NonceManager`], so concurrent runs never sign with the same one.
+    /// Returns the transaction hash.
+    pub async fn execute(
+        &self,
+        account: FieldElement,
+        calls: &[Call],
+        signer: &mut InvokeSigner<'_>,
+        fee_multiplier: f64,
+    ) -> Result<FieldElement> {
+        // Anything below 1.0 (or NaN) prices the transaction under its own estimate.
+        if !(fee_multiplier.is_finite() && fee_multiplier >= 1.0) {
+            return Err(anyhow::anyhow!("fee_multiplier must be a finite number >= 1.0, got {}", fee_multiplier));
+        }
+        let (idx, (mut tx, per_resource)) = self.with_failover(|idx| async move {
+            Ok((idx, self.prepare_invoke(idx, account, calls).await?))
+        }).await?;
+
+        let chain_nonce = nonce_to_u64(tx.nonce)?;
+        self.release_dropped_nonces(idx, account, chain_nonce).await?;
+        let nonce = self.nonces.reserve(account, chain_nonce)?;
+        let gaps = self.nonces.gaps(account, chain_nonce)?;
+        if !gaps.is_empty() {
+            log::warn!("Nonce gaps for {:#064x}: {:?}; later transactions wait on them", account, gaps);
+        }
+        tx.nonce = FieldElement::from(nonce);
+
+        let (hash, signature) = match self.price_and_sign(idx, &mut tx, per_resource, signer, fee_multiplier).await {
+            Ok(signed) => signed,
+            Err(e) => {
+                self.release_after_failure(account, nonce);
+                return Err(e);
+            }
+        };
+
+        let result = match self.rpc_request(idx, "starknet_addInvokeTransaction", json!({
+            "invoke_transaction": tx.to_rpc_json(&signature),
+        })).await {
+            Ok(result) => result,
+            Err(e) => {
+                if e.is::<RpcError>() {
+                    // The node refused it outright; nothing holds the nonce.
+                    self.release_after_failure(account, nonce);
+                } else if let Err(mark_err) = self.nonces.mark_submitted(account, nonce, hash) {
+                    // Lost in transit: it may still have arrived, so keep the nonce until
+                    // the hash is found or known dropped.
+                    log::warn!("Failed to record nonce {} as submitted: {:#}", nonce, mark_err);
+                }
+                return Err(e);
+            }
+        };
+
+        let raw = result.get("transaction_hash").and_then(Value::as_str)
+            .context("starknet_addInvokeTransaction returned no transaction hash")?;
+        let submitted = FieldElement::from_hex_be(raw)
+            .map_err(|_| anyhow::anyhow!("Invalid transaction hash: {}", raw))?;
+        if submitted != hash {
+            log::warn!("Node reported transaction hash {:#064x}, computed {:#064x}", submitted, hash);
+        }
+        self.nonces.mark_submitted(account, nonce, submitted)?;
+        Ok(submitted)
+    }
+
+    /// Best-effort release on an error path, so the original error is the one reported.
+    /// A nonce that cannot be released now expires after `UNSUBMITTED_TIMEOUT_SECS`.
+    fn release_after_failure(&self, account: FieldElement, nonce: u64) {
+        if let Err(e) = self.nonces.release(account, nonce) {
+            log::warn!("Failed to release nonce {}: {:#}", nonce, e);
+        }
+    }
+
+    /// Estimate `tx`, set its resource bounds and sign it.
+    async fn price_and_sign(
+        &self,
+        idx: usize,
+        tx: &mut InvokeV3,
+        per_resource: bool,
+        signer: &mut InvokeSigner<'_>,
+        fee_multiplier: f64,
+    ) -> Result<(FieldElement, Vec<FieldElement>)> {
+        let estimate = self.estimate_invoke_fee(idx, tx).await
+            .context("Failed to estimate fee")?;
+        tx.resource_bounds = estimate.resource_bounds(fee_multiplier, per_resource);
+
+        let hash = tx.hash()?;
+        let signature = signer.sign(tx)?;
+        Ok((hash, signature))
+    }
+
+    /// Poll `transaction_hash` until it is accepted (L2 or L1), reverted or rejected,
+    /// then return its decoded receipt. A hash the node does not know yet counts as
+    /// still pending; running out of `options.timeout` is a [`WatchTimeout`].
+    pub async fn wait_for_transaction(&self, transaction_hash: FieldElement, options: WatchOptions) -> Result<TxReceipt> {
+        let started = Instant::now();
+        let hash_param = json!({ "transaction_hash": format!("{:#x}", transaction_hash) });
+        let mut last_status = "NOT_RECEIVED".to_string();
+
+        loop {
+            let status = self.with_failover(|idx| self.rpc_request(idx, "starknet_getTransactionStatus", hash_param.clone())).await;
+            match status {
+                Ok(status) => {
+                    let finality = status.get("finality_status").and_then(Value::as_str).unwrap_or_default();
+                    match finality {
+                        "REJECTED" => {
+                            let reason = status.get("failure_reason").and_then(Value::as_str).map(str::to_string);
+                            return Ok(TxReceipt::rejected(transaction_hash, reason));
+                        }
+                        "ACCEPTED_ON_L2" | "ACCEPTED_ON_L1" => {
+                            let receipt = self.with_failover(|idx| {
+                                self.rpc_request(idx, "starknet_getTransactionReceipt", hash_param.clone())
+                            }).await?;
+                            return TxReceipt::from_rpc_json(&receipt);
+                        }
+                        other => last_status = other.to_string(),
+                    }
+                }
+                Err(e) if e.downcast_ref::<RpcError>().is_some_and(|rpc| rpc.code == TXN_HASH_NOT_FOUND) => {}
+                Err(e) => return Err(e),
+            }
+
+            let elapsed = started.elapsed();
+            if elapsed >= options.timeout {
+                return Err(WatchTimeout {
+                    transaction_hash,
+                    waited_secs: elapsed.as_secs(),
+                    last_status,
+                }.into());
+            }
+            tokio::time::sleep(options.poll_interval.min(options.timeout - elapsed)).await;
+        }
+    }
+
+    pub async fn get_network_status(&self) -> Result<(u64, u128)> {
         use starknet::core::types::{BlockId, BlockTag, MaybePendingBlockWithTxHashes};
 
-        let provider = self.next_provider();
-        
-        let block = provider.get_block_with_tx_hashes(BlockId::Tag(BlockTag::Latest)).await
-            .map_err(|e| anyhow::anyhow!("Failed to fetch block: {}", e))?;
+        let (idx, block) = self.with_failover(|idx| async move {
+            let block = self.tracked(idx, async {
+                self.providers[idx].get_block_with_tx_hashes(BlockId::Tag(BlockTag::Latest)).await
+                    .map_err(|e| provider_err("Failed to fetch block", e))
+            }).await?;
+            Ok((idx, block))
+        }).await?;
 
         match block {
             MaybePendingBlockWithTxHashes::Block(b) => {
+                self.health.record_block(idx, b.block_number);
                 // l1_gas_price is FieldElement in this version.
                 // Convert via string to avoid trait complexity (Felt -> u128)
                 let gas_felt = b.l1_gas_price.price_in_wei; 
@@ -110,46 +727,84 @@ impl StarknetClient {
         }
     }
 
+    /// ETH balance of `address` in wei. Errors rather than truncate a balance above
+    /// 2^128 wei.
     pub async fn get_eth_balance(&self, address: &str) -> Result<u128> {
-        self.limiter.check().await;
-        use starknet::core::types::{BlockId, BlockTag, FunctionCall, FieldElement};
-        use starknet::core::utils::get_selector_from_name;
-        
-        let provider = self.next_provider();
-        let eth_contract = FieldElement::from_hex_be("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7")?;
-        let selector = get_selector_from_name("balanceOf")?;
-        let user_address = FieldElement::from_hex_be(address).context("Invalid address format")?;
+        let owner = FieldElement::from_hex_be(address).context("Invalid address format")?;
+        let balance = self.get_token_balance("ETH", owner).await?;
+        u128::try_from(balance.amount)
+    }
 
-        let call = FunctionCall {
-            contract_address: eth_contract,
-            entry_point_selector: selector,
-            calldata: vec![user_address],
+    /// `owner`'s balance of `token`: a registry symbol (`ETH`, `STRK`, `USDC`, `SWAY`)
+    /// or any ERC-20 address, whose symbol and decimals are then read from the token.
+    pub async fn get_token_balance(&self, token: &str, owner: FieldElement) -> Result<TokenBalance> {
+        let chain_id = self.expected_chain_id().await?;
+        let token = match tokens::lookup(chain_id, token)? {
+            Some(known) => known,
+            None => self.token_metadata(FieldElement::from_hex_be(token.trim())?).await?,
         };
+        let result = self.call_contract(token.address, &["balanceOf", "balance_of"], &[owner]).await
+            .context(format!("Failed to fetch {} balance", token.symbol))?;
+        let amount = tokens::decode_balance(&result)
+            .context(format!("Invalid {} balance", token.symbol))?;
+        Ok(TokenBalance { token, owner, amount })
+    }
 
-        let result = provider.call(call, BlockId::Tag(BlockTag::Latest)).await
-            .map_err(|e| anyhow::anyhow!("Failed to fetch balance: {}", e))?;
-            
-        // Uint256 is [low, high]
-        if result.len() < 2 {
-            return Ok(0);
+    /// Registry tokens with an address on the connected network.
+    pub async fn known_tokens(&self) -> Result<Vec<Token>> {
+        tokens::known_tokens(self.expected_chain_id().await?)
+    }
+
+    /// Symbol and decimals of a token outside the registry, read from the contract.
+    async fn token_metadata(&self, address: FieldElement) -> Result<Token> {
+        let symbol = self.call_contract(address, &["symbol"], &[]).await
+            .context(format!("Failed to read symbol of token {:#x}", address))?;
+        let decimals = self.call_contract(address, &["decimals"], &[]).await
+            .context(format!("Failed to read decimals of token {:#x}", address))?;
+        let decimals = match decimals.as_slice() {
+            [decimals] => u8::try_from(*decimals)
+                .map_err(|_| anyhow::anyhow!("Token {:#x} reports out-of-range decimals: {:#x}", address, decimals))?,
+            other => anyhow::bail!("decimals() of token {:#x} returned {} felts", address, other.len()),
+        };
+        Ok(Token { symbol: tokens::decode_symbol(&symbol)?, decimals, address })
+    }
+
+    /// Call the first of `entry_points` the contract answers to at the latest block;
+    /// ERC-20 tokens of different generations spell their entry points differently.
+    async fn call_contract(&self, contract: FieldElement, entry_points: &[&str], calldata: &[FieldElement]) -> Result<Vec<FieldElement>> {
+        use starknet::core::types::{BlockId, BlockTag, FunctionCall};
+        use starknet::core::utils::get_selector_from_name;
+
+        let mut last_err = None;
+        for name in entry_points {
+            let call = FunctionCall {
+                contract_address: contract,
+                entry_point_selector: get_selector_from_name(name)?,
+                calldata: calldata.to_vec(),
+            };
+            let call = &call;
+            let result = self.with_failover(|idx| self.tracked(idx, async move {
+                self.providers[idx].call(call.clone(), BlockId::Tag(BlockTag::Latest)).await
+                    .map_err(|e| provider_err(&format!("Failed to call {}", name), e))
+            })).await;
+            match result {
+                // A Starknet error (no such entry point, contract error): try the next spelling.
+                Err(e) if !is_provider_fault(&e) => last_err = Some(e),
+                result => return result,
+            }
         }
-        
-        // Convert low part to u128. High part ignored (safe for < 3.4 * 10^38 Wei)
-        let low = result[0];
-        let balance: u128 = format!("{}", low).parse().unwrap_or(0);
-        
-        Ok(balance)
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("No entry point to call on {:#x}", contract)))
     }
 
     pub async fn get_nonce(&self, address: &str) -> Result<String> {
-        self.limiter.check().await;
         use starknet::core::types::{BlockId, BlockTag, FieldElement};
         
-        let provider = self.next_provider();
         let user_address = FieldElement::from_hex_be(address).context("Invalid address format")?;
 
-        let nonce = provider.get_nonce(BlockId::Tag(BlockTag::Latest), user_address).await
-            .map_err(|e| anyhow::anyhow!("Failed to fetch nonce: {}", e))?;
+        let nonce = self.with_failover(|idx| self.tracked(idx, async move {
+            self.providers[idx].get_nonce(BlockId::Tag(BlockTag::Latest), user_address).await
+                .map_err(|e| provider_err("Failed to fetch nonce", e))
+        })).await?;
             
         Ok(format!("{}", nonce))
     }
@@ -167,14 +822,216 @@ impl StarknetClient {
         Ok((is_busy, food_level))
     }
 
-    /// Execute a batched query (Multicall).
-    pub async fn batch_query(&self, _account_address: &str, _asteroids: &[u64]) -> Result<String> {
-        self.limiter.check().await;
-        let _provider = self.next_provider();
-        
-        // logic to construct a Multicall transaction or multiple async queries
-        // For v0.1.0, we will simulate this.
-        
-        Ok("{\"balance\": \"1000 SWAY\", \"asteroids\": []}".to_string())
+    /// SWAY balance of `account_address` and the owner of each of `asteroids`, all
+    /// read at one block in as few requests as the provider allows. Asteroid owners
+    /// come from the ERC-721 at `$INFLUENCE_ASTEROID_ADDRESS`; an asteroid whose read
+    /// fails (e.g. not minted) carries the error instead of failing the query. Lot
+    /// state is not read (see [`AsteroidOwnership`]).
+    pub async fn batch_query(&self, account_address: &str, asteroids: &[u64]) -> Result<BatchQueryResult> {
+        use starknet::core::utils::get_selector_from_name;
+
+        let account = FieldElement::from_hex_be(account_address).context("Invalid address format")?;
+        let chain_id = self.expected_chain_id().await?;
+        let sway = tokens::lookup(chain_id, "SWAY")?.context("SWAY is not a registry token")?;
+        let asteroid_contract = if asteroids.is_empty() {
+            FieldElement::ZERO
+        } else {
+            let raw = env::var(ASTEROID_CONTRACT_ENV)
+                .context(format!("Set {} to read asteroid owners", ASTEROID_CONTRACT_ENV))?;
+            FieldElement::from_hex_be(raw.trim())
+                .map_err(|_| anyhow::anyhow!("Invalid {}: {}", ASTEROID_CONTRACT_ENV, raw))?
+        };
+        let balance_of = get_selector_from_name("balanceOf")?;
+        let owner_of = get_selector_from_name("ownerOf")?;
+
+        let (block_number, results, batched) = self.with_failover(|idx| {
+            let sway = &sway;
+            async move {
+                let block_number = self.rpc_request(idx, "starknet_blockNumber", json!([])).await?
+                    .as_u64().context("starknet_blockNumber did not return a number")?;
+                let block_id = json!({ "block_number": block_number });
+                let call = |contract: FieldElement, selector: FieldElement, calldata: Vec<FieldElement>| json!({
+                    "request": {
+                        "contract_address": format!("{:#x}", contract),
+                        "entry_point_selector": format!("{:#x}", selector),
+                        "calldata": calldata.iter().map(|d| format!("{:#x}", d)).collect::<Vec<_>>(),
+                    },
+                    "block_id": block_id,
+                });
+                let mut calls = vec![call(sway.address, balance_of, vec![account])];
+                // Token ids are u256: `[low, high]`.
+                calls.extend(asteroids.iter().map(|id| call(asteroid_contract, owner_of, vec![FieldElement::from(*id), FieldElement::ZERO])));
+
+                let (results, batched) = self.call_many(idx, &calls).await?;
+                Ok((block_number, results, batched))
+            }
+        }).await?;
+
+        let mut results = results.into_iter();
+        let balance = results.next().context("starknet_call returned no SWAY balance")?
+            .context("Failed to fetch SWAY balance")?;
+        let balance = felts_from_json("starknet_call", &balance)?;
+        let amount = tokens::decode_balance(&balance).context("Invalid SWAY balance")?;
+
+        let asteroid_owners = asteroids.iter().zip(results)
+            .map(|(id, result)| asteroid_ownership(*id, result))
+            .collect();
+
+        Ok(BatchQueryResult {
+            account,
+            block_number,
+            sway: TokenBalance { token: sway, owner: account, amount },
+            asteroid_owners,
+            batched,
+        })
+    }
+}
+
+/// JSON-RPC batch bodies for `calls` (`starknet_call` params), at most
+/// [`MAX_BATCH_CALLS`] requests each. Ids restart at 0 in every batch.
+fn batch_bodies(calls: &[Value]) -> Vec<Vec<Value>> {
+    calls.chunks(MAX_BATCH_CALLS)
+        .map(|chunk| chunk.iter().enumerate()
+            .map(|(id, params)| json!({ "jsonrpc": "2.0", "id": id, "method": "starknet_call", "params": params }))
+            .collect())
+        .collect()
+}
+
+/// Results of a batch of `len` requests in request order, matched by id since
+/// responses may come back in any order. `None` when one is unanswered.
+fn batch_results(len: usize, responses: &[Value]) -> Option<Vec<Result<Value>>> {
+    let mut results: Vec<Option<Result<Value>>> = (0..len).map(|_| None).collect();
+    for response in responses {
+        let slot = response.get("id").and_then(Value::as_u64)
+            .and_then(|id| results.get_mut(id as usize));
+        if let Some(slot) = slot {
+            *slot = Some(rpc_result("starknet_call", response));
+        }
+    }
+    results.into_iter().collect()
+}
+
+/// Owner of `asteroid_id` from its `ownerOf` result; a failed or malformed read is
+/// kept as that asteroid's error.
+fn asteroid_ownership(asteroid_id: u64, result: Result<Value>) -> AsteroidOwnership {
+    let owner = result.and_then(|owner| match felts_from_json("starknet_call", &owner)?.as_slice() {
+        [owner] => Ok(*owner),
+        other => anyhow::bail!("ownerOf returned {} felts", other.len()),
+    });
+    match owner {
+        Ok(owner) => AsteroidOwnership { asteroid_id, owner: Some(owner), error: None },
+        Err(e) => AsteroidOwnership { asteroid_id, owner: None, error: Some(format!("{:#}", e)) },
+    }
+}
+
+/// `result` of a JSON-RPC response, or its `error` as an [`RpcError`].
+fn rpc_result(method: &str, response: &Value) -> Result<Value> {
+    if let Some(error) = response.get("error") {
+        return Err(RpcError {
+            method: method.to_string(),
+            code: error.get("code").and_then(Value::as_i64).unwrap_or_default(),
+            message: error.get("message").and_then(Value::as_str).unwrap_or("unknown error").to_string(),
+            data: error.get("data").cloned(),
+        }.into());
+    }
+    response.get("result").cloned()
+        .ok_or_else(|| ProviderFault(format!("{} returned no result", method)).into())
+}
+
+fn felts_from_json(method: &str, value: &Value) -> Result<Vec<FieldElement>> {
+    value.as_array().context(format!("{} did not return an array", method))?
+        .iter()
+        .map(|felt| felt_from_json(method, felt))
+        .collect()
+}
+
+fn felt_from_json(method: &str, value: &Value) -> Result<FieldElement> {
+    let raw = value.as_str().context(format!("{} did not return a felt", method))?;
+    FieldElement::from_hex_be(raw).map_err(|_| anyhow::anyhow!("{} returned an invalid felt: {}", method, raw))
+}
+
+fn nonce_to_u64(nonce: FieldElement) -> Result<u64> {
+    u64::try_from(nonce).map_err(|_| anyhow::anyhow!("Nonce out of range: {:#x}", nonce))
+}
+
+/// Keep the message format of the other client errors, but mark transport-level
+/// failures as [`ProviderFault`] so they fail over. Starknet error codes this
+/// `starknet-rs` does not know arrive as raw JSON-RPC errors and count as answers too.
+fn provider_err(context: &str, e: ProviderError) -> anyhow::Error {
+    let answered = match &e {
+        ProviderError::StarknetError(_) => true,
+        ProviderError::Other(inner) => matches!(
+            inner.as_any().downcast_ref::<JsonRpcClientError<HttpTransportError>>(),
+            Some(JsonRpcClientError::JsonRpcError(rpc)) if rpc.code >= 0
+        ),
+        _ => false,
+    };
+    if answered {
+        anyhow::anyhow!("{}: {}", context, e)
+    } else {
+        ProviderFault(format!("{}: {}", context, e)).into()
+    }
+}
+
+/// Whether `e` says the endpoint is unwell rather than that the request was wrong.
+/// Negative JSON-RPC codes are protocol-level (-32603 internal error, -32005 limit
+/// exceeded, ...); Starknet's own error codes are positive.
+fn is_provider_fault(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| {
+        cause.is::<ProviderFault>() || cause.downcast_ref::<RpcError>().is_some_and(|rpc| rpc.code < 0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(id: u64) -> Value {
+        json!({ "request": { "calldata": [format!("{:#x}", id)] }, "block_id": "latest" })
+    }
+
+    #[test]
+    fn batches_are_chunked_at_max_batch_calls() {
+        let calls: Vec<Value> = (0..2 * MAX_BATCH_CALLS as u64 + 1).map(call).collect();
+        let bodies = batch_bodies(&calls);
+        assert_eq!(bodies.iter().map(Vec::len).collect::<Vec<_>>(), [MAX_BATCH_CALLS, MAX_BATCH_CALLS, 1]);
+        // Ids index into their own batch; params keep the call order across batches.
+        assert_eq!(bodies[1][0]["id"], 0);
+        assert_eq!(bodies[1][0]["params"], calls[MAX_BATCH_CALLS]);
+        assert_eq!(bodies[2][0]["params"], calls[2 * MAX_BATCH_CALLS]);
+        assert_eq!(batch_bodies(&calls[..MAX_BATCH_CALLS]).len(), 1);
+        assert!(batch_bodies(&[]).is_empty());
+    }
+
+    #[test]
+    fn batch_responses_are_matched_by_id() {
+        let responses = [
+            json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": 40, "message": "Contract error" } }),
+            json!({ "jsonrpc": "2.0", "id": 0, "result": ["0x7"] }),
+        ];
+        let results = batch_results(2, &responses).unwrap();
+        assert_eq!(results[0].as_ref().unwrap(), &json!(["0x7"]));
+        let error = results[1].as_ref().unwrap_err().downcast_ref::<RpcError>().unwrap();
+        assert_eq!(error.code, 40);
+        // A batch with an unanswered request is treated as unsupported.
+        assert!(batch_results(3, &responses).is_none());
+    }
+
+    #[test]
+    fn asteroid_failures_stay_per_asteroid() {
+        let owner = FieldElement::from_hex_be("0xabc").unwrap();
+        let owned = asteroid_ownership(1, Ok(json!(["0xabc"])));
+        assert_eq!(owned, AsteroidOwnership { asteroid_id: 1, owner: Some(owner), error: None });
+        assert!(owned.owned_by(owner));
+
+        let unminted = asteroid_ownership(2, rpc_result("starknet_call", &json!({
+            "error": { "code": 40, "message": "Contract error" },
+        })));
+        assert_eq!(unminted.owner, None);
+        assert!(unminted.error.unwrap().contains("Contract error"));
+
+        let malformed = asteroid_ownership(3, Ok(json!(["0x1", "0x2"])));
+        assert_eq!(malformed.error.as_deref(), Some("ownerOf returned 2 felts"));
+        assert!(asteroid_ownership(4, Ok(json!("0x1"))).error.is_some());
     }
 }
@@ -0,0 +1,116 @@
+use anyhow::{Context, Result};
+use fs2::FileExt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// JSON state shared by every process on this machine (nonce reservations, the
+/// revocation list). Changes re-read the file under an exclusive lock on
+/// `<path>.lock` and replace it through `<path>.tmp` + rename, so overlapping pulses
+/// never lose each other's updates and a crash never leaves a half-written file.
+pub struct StateFile<T> {
+    path: PathBuf,
+    /// What the file holds, for error messages ("nonce state", "revocation list").
+    what: &'static str,
+    state: PhantomData<fn() -> T>,
+}
+
+impl<T: Default + Serialize + DeserializeOwned> StateFile<T> {
+    pub fn new<P: AsRef<Path>>(path: P, what: &'static str) -> Self {
+        StateFile { path: path.as_ref().to_path_buf(), what, state: PhantomData }
+    }
+
+    /// A missing file is the default state; a corrupt one is an error, so callers
+    /// fail closed.
+    pub fn read(&self) -> Result<T> {
+        match fs::read(&self.path) {
+            Ok(raw) => serde_json::from_slice(&raw)
+                .context(format!("Corrupt {}: {}", self.what, self.path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+            Err(e) => Err(e).context(format!("Failed to read {}: {}", self.what, self.path.display())),
+        }
+    }
+
+    /// Apply `change` to the current state under the lock and write the result back.
+    /// Nothing is written if `change` fails.
+    pub fn update<R>(&self, change: impl FnOnce(&mut T) -> Result<R>) -> Result<R> {
+        let lock = self.lock()?;
+        let mut state = self.read()?;
+        let result = change(&mut state)?;
+        self.write_atomic(&state)?;
+        let _ = FileExt::unlock(&lock);
+        Ok(result)
+    }
+
+    fn write_atomic(&self, state: &T) -> Result<()> {
+        let tmp_path = self.sidecar("tmp");
+        {
+            let mut tmp = File::create(&tmp_path).context(format!("Failed to create {} temp file", self.what))?;
+            tmp.write_all(&serde_json::to_vec_pretty(state)?)?;
+            tmp.sync_all()?;
+        }
+        fs::rename(&tmp_path, &self.path).context(format!("Failed to replace {}", self.what))
+    }
+
+    fn lock(&self) -> Result<File> {
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.sidecar("lock"))
+            .context(format!("Failed to open {} lock file", self.what))?;
+        lock.lock_exclusive().context(format!("Failed to lock {}", self.what))?;
+        Ok(lock)
+    }
+
+    /// `<path>.<suffix>`
+    fn sidecar(&self, suffix: &str) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(".");
+        name.push(suffix);
+        PathBuf::from(name)
+    }
+}
+
+/// Path configured in `$var`, which must be absolute: a relative one would resolve
+/// against whatever directory the process happened to start in, so two runs could read
+/// different files. `Ok(None)` when unset or empty.
+pub fn absolute_path_env(var: &str) -> Result<Option<PathBuf>> {
+    match env::var_os(var) {
+        Some(value) if !value.is_empty() => {
+            let path = PathBuf::from(value);
+            if !path.is_absolute() {
+                return Err(anyhow::anyhow!("{} must be an absolute path, got {}", var, path.display()));
+            }
+            Ok(Some(path))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Seconds since the Unix epoch.
+pub fn unix_now() -> Result<u64> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configured_paths_must_be_absolute() {
+        let var = "STARK_STATE_FILE_TEST_PATH";
+        assert!(absolute_path_env(var).unwrap().is_none());
+        env::set_var(var, "relative/state.json");
+        assert!(absolute_path_env(var).is_err());
+        let absolute = env::temp_dir().join("state.json");
+        env::set_var(var, &absolute);
+        assert_eq!(absolute_path_env(var).unwrap(), Some(absolute));
+        env::remove_var(var);
+    }
+}
@@ -1,5 +1,5 @@
-use std::collections::{HashMap, HashSet};
-use anyhow::{Result, Context};
+use std::collections::HashMap;
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,7 +45,7 @@ impl SupplyChainGraph {
     pub fn add_recipe(&mut self, name: &str, recipe: Recipe) {
         self.recipes.insert(name.to_string(), recipe.clone());
         for output in recipe.outputs.keys() {
-            self.adjacency_list.entry(output.clone()).or_insert_with(Vec::new).push(name.to_string());
+            self.adjacency_list.entry(output.clone()).or_default().push(name.to_string());
         }
     }
 
@@ -0,0 +1,283 @@
+use anyhow::{Context, Result};
+use starknet::core::types::FieldElement;
+use starknet::core::utils::parse_cairo_short_string;
+use std::env;
+use std::fmt;
+
+/// Networks the built-in registry knows, by decoded chain id.
+pub const MAINNET: &str = "SN_MAIN";
+pub const SEPOLIA: &str = "SN_SEPOLIA";
+
+/// `(symbol, decimals, mainnet address, Sepolia address)`. ETH and STRK share their
+/// addresses across networks, devnets included; USDC is the StarkGate-bridged USDC.e
+/// on mainnet, which has no Sepolia counterpart (Circle's native USDC there is a
+/// different token); SWAY is Influence's in-game currency, which has no fixed Sepolia
+/// deployment.
+/// Addresses not listed here come from `<SYMBOL>_TOKEN_ADDRESS` (e.g.
+/// `SWAY_TOKEN_ADDRESS`), which also overrides a listed one.
+const KNOWN_TOKENS: [(&str, u8, Option<&str>, Option<&str>); 4] = [
+    (
+        "ETH",
+        18,
+        Some("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"),
+        Some("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"),
+    ),
+    (
+        "STRK",
+        18,
+        Some("0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d"),
+        Some("0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d"),
+    ),
+    (
+        "USDC",
+        6,
+        Some("0x053c91253bc9682c04929ca02ed00b3e423f6710d2ee7e0d5ebb06f3ecf368a8"),
+        None,
+    ),
+    ("SWAY", 6, Some("0x0030058f19ed447208015f6430f0102e8ab82d6c291566d7e73fe8e613c3d2ed"), None),
+];
+
+/// Unsigned 256-bit amount, as a Cairo `u256 { low, high }`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct U256 {
+    pub high: u128,
+    pub low: u128,
+}
+
+impl U256 {
+    /// From the two felts of a Cairo `u256`. Either limb not fitting in 128 bits is
+    /// an error, not a truncation.
+    pub fn from_felts(low: FieldElement, high: FieldElement) -> Result<Self> {
+        let limb = |felt: FieldElement, name: &str| {
+            u128::try_from(felt).map_err(|_| anyhow::anyhow!("u256 {} limb out of range: {:#x}", name, felt))
+        };
+        Ok(U256 { low: limb(low, "low")?, high: limb(high, "high")? })
+    }
+
+    /// Decimal digits of the amount shifted by `decimals`, e.g. `1.5` for
+    /// 1500000000000000000 at 18 decimals. Trailing zeros are kept so the precision
+    /// of the token stays visible.
+    pub fn format_units(&self, decimals: u8) -> String {
+        let digits = self.to_string();
+        let decimals = decimals as usize;
+        if decimals == 0 {
+            return digits;
+        }
+        let padded = format!("{:0>width$}", digits, width = decimals + 1);
+        let (whole, fraction) = padded.split_at(padded.len() - decimals);
+        format!("{}.{}", whole, fraction)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.high == 0 && self.low == 0
+    }
+
+    /// Divide by `divisor` in place and return the remainder, limb by 64-bit limb.
+    fn div_rem_u64(&mut self, divisor: u64) -> u64 {
+        let divisor = divisor as u128;
+        let mut remainder = 0u128;
+        let mut limbs = [self.high >> 64, self.high as u64 as u128, self.low >> 64, self.low as u64 as u128];
+        for limb in limbs.iter_mut() {
+            let current = (remainder << 64) | *limb;
+            *limb = current / divisor;
+            remainder = current % divisor;
+        }
+        self.high = (limbs[0] << 64) | limbs[1];
+        self.low = (limbs[2] << 64) | limbs[3];
+        remainder as u64
+    }
+}
+
+impl TryFrom<U256> for u128 {
+    type Error = anyhow::Error;
+
+    fn try_from(value: U256) -> Result<u128> {
+        if value.high != 0 {
+            anyhow::bail!("Amount {} does not fit in 128 bits", value);
+        }
+        Ok(value.low)
+    }
+}
+
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return f.write_str("0");
+        }
+        // 19 decimal digits at a time: the largest power of ten below 2^64.
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut rest = *self;
+        let mut chunks = Vec::new();
+        while !rest.is_zero() {
+            chunks.push(rest.div_rem_u64(CHUNK));
+        }
+        let mut out = chunks.pop().unwrap_or_default().to_string();
+        for chunk in chunks.iter().rev() {
+            out.push_str(&format!("{:019}", chunk));
+        }
+        f.write_str(&out)
+    }
+}
+
+/// An ERC-20 token on one network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub symbol: String,
+    pub decimals: u8,
+    pub address: FieldElement,
+}
+
+/// `owner`'s balance of `token`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenBalance {
+    pub token: Token,
+    pub owner: FieldElement,
+    pub amount: U256,
+}
+
+impl TokenBalance {
+    pub fn formatted(&self) -> String {
+        self.amount.format_units(self.token.decimals)
+    }
+}
+
+/// Tokens of the registry known on `chain_id`, with `<SYMBOL>_TOKEN_ADDRESS`
+/// overrides applied. Tokens without an address on that network are left out.
+pub fn known_tokens(chain_id: FieldElement) -> Result<Vec<Token>> {
+    let network = parse_cairo_short_string(&chain_id).unwrap_or_default();
+    KNOWN_TOKENS.iter()
+        .filter_map(|(symbol, decimals, mainnet, sepolia)| {
+            let builtin = match network.as_str() {
+                MAINNET => *mainnet,
+                SEPOLIA => *sepolia,
+                // Devnets predeploy only the tokens that live at one address everywhere.
+                _ => mainnet.filter(|_| mainnet == sepolia),
+            };
+            let env_key = format!("{}_TOKEN_ADDRESS", symbol);
+            let address = match env::var(&env_key) {
+                Ok(raw) if !raw.trim().is_empty() => Some((raw.trim().to_string(), env_key)),
+                _ => builtin.map(|a| (a.to_string(), env_key)),
+            }?;
+            Some(FieldElement::from_hex_be(&address.0)
+                .map_err(|_| anyhow::anyhow!("Invalid {}: {}", address.1, address.0))
+                .map(|address| Token { symbol: symbol.to_string(), decimals: *decimals, address }))
+        })
+        .collect()
+}
+
+/// Registry entry for `token` (a symbol, case-insensitive, or an address) on
+/// `chain_id`; `None` for an address the registry does not know.
+pub fn lookup(chain_id: FieldElement, token: &str) -> Result<Option<Token>> {
+    let tokens = known_tokens(chain_id)?;
+    let token = token.trim();
+    if token.starts_with("0x") {
+        let address = FieldElement::from_hex_be(token).map_err(|_| anyhow::anyhow!("Invalid token address: {}", token))?;
+        return Ok(tokens.into_iter().find(|t| t.address == address));
+    }
+    let found = tokens.into_iter().find(|t| t.symbol.eq_ignore_ascii_case(token));
+    match found {
+        Some(found) => Ok(Some(found)),
+        None if KNOWN_TOKENS.iter().any(|(symbol, ..)| symbol.eq_ignore_ascii_case(token)) => Err(anyhow::anyhow!(
+            "{} has no known address on {}; set {}_TOKEN_ADDRESS",
+            token.to_uppercase(),
+            parse_cairo_short_string(&chain_id).unwrap_or_else(|_| format!("{:#x}", chain_id)),
+            token.to_uppercase()
+        )),
+        None => Err(anyhow::anyhow!("Unknown token symbol: {} (pass its address instead)", token)),
+    }
+}
+
+/// Decode an ERC-20 `symbol()` result: a short string felt (Cairo 0 and early
+/// Cairo 1 tokens) or a `ByteArray` (`[n, word_1..word_n, pending, pending_len]`).
+pub fn decode_symbol(result: &[FieldElement]) -> Result<String> {
+    match result {
+        [felt] => parse_cairo_short_string(felt).context("symbol() is not a short string"),
+        [count, rest @ ..] => {
+            let count = usize::try_from(u64::try_from(*count).context("Invalid ByteArray length")?)?;
+            let (words, pending) = rest.split_at_checked(count).context("Truncated ByteArray")?;
+            let mut symbol = String::new();
+            for word in words {
+                symbol.push_str(&parse_cairo_short_string(word).context("Invalid ByteArray word")?);
+            }
+            if let [pending_word, _pending_len] = pending {
+                symbol.push_str(&parse_cairo_short_string(pending_word).context("Invalid ByteArray pending word")?);
+            }
+            Ok(symbol)
+        }
+        [] => anyhow::bail!("symbol() returned nothing"),
+    }
+}
+
+/// Decode an ERC-20 `balanceOf` result into a u256.
+pub fn decode_balance(result: &[FieldElement]) -> Result<U256> {
+    match result {
+        [low, high] => U256::from_felts(*low, *high),
+        other => anyhow::bail!("balanceOf returned {} felts, expected a u256 (2)", other.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::core::utils::cairo_short_string_to_felt;
+
+    fn short(text: &str) -> FieldElement {
+        cairo_short_string_to_felt(text).unwrap()
+    }
+
+    /// 2^128: the smallest amount that needs the high limb.
+    const TWO_POW_128: U256 = U256 { high: 1, low: 0 };
+
+    #[test]
+    fn u256_display() {
+        assert_eq!(U256::default().to_string(), "0");
+        assert_eq!(U256 { high: 0, low: u128::MAX }.to_string(), "340282366920938463463374607431768211455");
+        assert_eq!(TWO_POW_128.to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(
+            U256 { high: u128::MAX, low: u128::MAX }.to_string(),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+    }
+
+    #[test]
+    fn format_units_by_decimals() {
+        let amount = U256 { high: 0, low: 1_500_000 };
+        assert_eq!(amount.format_units(6), "1.500000");
+        assert_eq!(amount.format_units(18), "0.000000000001500000");
+        assert_eq!(amount.format_units(0), "1500000");
+        assert_eq!(U256 { high: 0, low: 1_500_000_000_000_000_000 }.format_units(18), "1.500000000000000000");
+        assert_eq!(TWO_POW_128.format_units(18), "340282366920938463463.374607431768211456");
+    }
+
+    #[test]
+    fn decode_balance_limbs() {
+        assert_eq!(decode_balance(&[FieldElement::from(5u64), FieldElement::ZERO]).unwrap(), U256 { high: 0, low: 5 });
+        assert_eq!(decode_balance(&[FieldElement::ZERO, FieldElement::ONE]).unwrap(), TWO_POW_128);
+        // A bare felt (Cairo 0 `felt` balance) or a limb over 128 bits is malformed.
+        assert!(decode_balance(&[FieldElement::from(5u64)]).is_err());
+        assert!(decode_balance(&[FieldElement::from(5u64), FieldElement::ZERO, FieldElement::ZERO]).is_err());
+        let wide = FieldElement::from_hex_be("0x100000000000000000000000000000000").unwrap();
+        assert!(decode_balance(&[wide, FieldElement::ZERO]).is_err());
+    }
+
+    #[test]
+    fn decode_symbol_short_string_and_byte_array() {
+        assert_eq!(decode_symbol(&[short("USDC")]).unwrap(), "USDC");
+        // ByteArray with no full word: [0, pending, pending_len].
+        assert_eq!(decode_symbol(&[FieldElement::ZERO, short("STRK"), FieldElement::from(4u64)]).unwrap(), "STRK");
+        let word = "A".repeat(31);
+        assert_eq!(
+            decode_symbol(&[FieldElement::ONE, short(&word), short("B"), FieldElement::ONE]).unwrap(),
+            format!("{}B", word)
+        );
+        assert!(decode_symbol(&[]).is_err());
+        assert!(decode_symbol(&[FieldElement::from(3u64), short("A")]).is_err());
+    }
+
+    #[test]
+    fn usdc_is_only_pinned_on_mainnet() {
+        let usdc = |network: &str| known_tokens(short(network)).unwrap().into_iter().find(|t| t.symbol == "USDC");
+        assert_eq!(usdc(MAINNET).map(|t| t.decimals), Some(6));
+        assert!(usdc(SEPOLIA).is_none());
+    }
+}
@@ -0,0 +1,343 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use starknet::accounts::Call;
+use starknet::core::types::FieldElement;
+use starknet::core::utils::cairo_short_string_to_felt;
+use starknet_crypto::poseidon_hash_many;
+use crate::session_keys::SessionKey;
+use crate::vault::Vault;
+
+/// Upper limit on one resource, as the sequencer accepts it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceBound {
+    pub max_amount: u64,
+    pub max_price_per_unit: u128,
+}
+
+/// Fee limits of a v3 transaction (paid in STRK). `l1_data_gas` is only part of the
+/// transaction from RPC 0.8 / Starknet 0.13.4 on; `None` builds the older two-resource form.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceBounds {
+    pub l1_gas: ResourceBound,
+    pub l2_gas: ResourceBound,
+    pub l1_data_gas: Option<ResourceBound>,
+}
+
+/// INVOKE v3 transaction (SNIP-8) from a Cairo 1 account, with L1 data availability
+/// for both nonce and fee, no tip, no paymaster and no deployment data.
+#[derive(Debug, Clone)]
+pub struct InvokeV3 {
+    pub sender_address: FieldElement,
+    pub calldata: Vec<FieldElement>,
+    pub nonce: FieldElement,
+    pub chain_id: FieldElement,
+    pub resource_bounds: ResourceBounds,
+    pub tip: u64,
+    /// Query-only version: valid for estimates and simulations, never for execution.
+    pub is_query: bool,
+}
+
+impl InvokeV3 {
+    pub fn new(sender_address: FieldElement, calls: &[Call], nonce: FieldElement, chain_id: FieldElement) -> Self {
+        InvokeV3 {
+            sender_address,
+            calldata: encode_calls(calls),
+            nonce,
+            chain_id,
+            resource_bounds: ResourceBounds::default(),
+            tip: 0,
+            is_query: false,
+        }
+    }
+
+    fn version(&self) -> FieldElement {
+        // Query-only transactions carry `2^128 + 3`, so they can never be replayed.
+        let version = FieldElement::THREE;
+        if self.is_query { version + FieldElement::from(u128::MAX) + FieldElement::ONE } else { version }
+    }
+
+    /// Poseidon transaction hash the account signs.
+    pub fn hash(&self) -> Result<FieldElement> {
+        let mut fee_fields = vec![
+            FieldElement::from(self.tip),
+            resource_bound_felt("L1_GAS", &self.resource_bounds.l1_gas)?,
+            resource_bound_felt("L2_GAS", &self.resource_bounds.l2_gas)?,
+        ];
+        if let Some(l1_data_gas) = &self.resource_bounds.l1_data_gas {
+            fee_fields.push(resource_bound_felt("L1_DATA", l1_data_gas)?);
+        }
+
+        Ok(poseidon_hash_many(&[
+            cairo_short_string_to_felt("invoke")?,
+            self.version(),
+            self.sender_address,
+            poseidon_hash_many(&fee_fields),
+            poseidon_hash_many(&[]), // paymaster_data
+            self.chain_id,
+            self.nonce,
+            FieldElement::ZERO, // nonce DA mode (L1) << 32 | fee DA mode (L1)
+            poseidon_hash_many(&[]), // account_deployment_data
+            poseidon_hash_many(&self.calldata),
+        ]))
+    }
+
+    /// `BROADCASTED_INVOKE_TXN` JSON for `starknet_addInvokeTransaction` and friends.
+    pub fn to_rpc_json(&self, signature: &[FieldElement]) -> Value {
+        let hex = |felt: &FieldElement| format!("{:#x}", felt);
+        let bound = |b: &ResourceBound| json!({
+            "max_amount": format!("{:#x}", b.max_amount),
+            "max_price_per_unit": format!("{:#x}", b.max_price_per_unit),
+        });
+
+        let mut resource_bounds = json!({
+            "l1_gas": bound(&self.resource_bounds.l1_gas),
+            "l2_gas": bound(&self.resource_bounds.l2_gas),
+        });
+        if let Some(l1_data_gas) = &self.resource_bounds.l1_data_gas {
+            resource_bounds["l1_data_gas"] = bound(l1_data_gas);
+        }
+
+        json!({
+            "type": "INVOKE",
+            "version": hex(&self.version()),
+            "sender_address": hex(&self.sender_address),
+            "calldata": self.calldata.iter().map(hex).collect::<Vec<_>>(),
+            "signature": signature.iter().map(hex).collect::<Vec<_>>(),
+            "nonce": hex(&self.nonce),
+            "resource_bounds": resource_bounds,
+            "tip": format!("{:#x}", self.tip),
+            "paymaster_data": [],
+            "account_deployment_data": [],
+            "nonce_data_availability_mode": "L1",
+            "fee_data_availability_mode": "L1",
+        })
+    }
+}
+
+/// Result of `starknet_estimateFee` for one transaction, normalised across RPC versions:
+/// 0.6 only reports L1 gas, 0.7 adds L1 data gas, 0.8 reports all three resources.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub l1_gas_consumed: u128,
+    pub l1_gas_price: u128,
+    pub l1_data_gas_consumed: u128,
+    pub l1_data_gas_price: u128,
+    pub l2_gas_consumed: u128,
+    pub l2_gas_price: u128,
+    pub overall_fee: u128,
+    /// `"FRI"` (STRK) or `"WEI"` (ETH).
+    pub unit: String,
+}
+
+impl FeeEstimate {
+    pub fn from_rpc_json(value: &Value) -> Result<Self> {
+        let field = |key: &str| -> Result<Option<u128>> {
+            match value.get(key) {
+                None | Some(Value::Null) => Ok(None),
+                Some(v) => {
+                    let raw = v.as_str().context(format!("Fee estimate field '{}' is not a string", key))?;
+                    let felt = FieldElement::from_hex_be(raw)
+                        .map_err(|_| anyhow::anyhow!("Invalid fee estimate field '{}': {}", key, raw))?;
+                    let amount = u128::try_from(felt)
+                        .map_err(|_| anyhow::anyhow!("Fee estimate field '{}' overflows u128", key))?;
+                    Ok(Some(amount))
+                }
+            }
+        };
+
+        Ok(FeeEstimate {
+            l1_gas_consumed: field("l1_gas_consumed")?.or(field("gas_consumed")?).unwrap_or_default(),
+            l1_gas_price: field("l1_gas_price")?.or(field("gas_price")?).unwrap_or_default(),
+            l1_data_gas_consumed: field("l1_data_gas_consumed")?.or(field("data_gas_consumed")?).unwrap_or_default(),
+            l1_data_gas_price: field("l1_data_gas_price")?.or(field("data_gas_price")?).unwrap_or_default(),
+            l2_gas_consumed: field("l2_gas_consumed")?.unwrap_or_default(),
+            l2_gas_price: field("l2_gas_price")?.unwrap_or_default(),
+            overall_fee: field("overall_fee")?.context("Fee estimate has no overall_fee")?,
+            unit: value.get("unit").and_then(Value::as_str).unwrap_or("FRI").to_string(),
+        })
+    }
+
+    /// Resource bounds covering this estimate with `multiplier` headroom on both amount
+    /// and price. Without `per_resource` (RPC < 0.8) the whole fee, data gas included,
+    /// is expressed as L1 gas, since that is the only bound the sequencer charges.
+    pub fn resource_bounds(&self, multiplier: f64, per_resource: bool) -> ResourceBounds {
+        let bound = |amount: u128, price: u128| ResourceBound {
+            max_amount: u64::try_from(scale(amount, multiplier)).unwrap_or(u64::MAX),
+            max_price_per_unit: scale(price, multiplier),
+        };
+
+        if per_resource {
+            ResourceBounds {
+                l1_gas: bound(self.l1_gas_consumed, self.l1_gas_price),
+                l2_gas: bound(self.l2_gas_consumed, self.l2_gas_price),
+                l1_data_gas: Some(bound(self.l1_data_gas_consumed, self.l1_data_gas_price)),
+            }
+        } else {
+            let price = self.l1_gas_price.max(1);
+            ResourceBounds {
+                l1_gas: bound(self.overall_fee.div_ceil(price), price),
+                l2_gas: ResourceBound::default(),
+                l1_data_gas: None,
+            }
+        }
+    }
+}
+
+/// One entry of `starknet_simulateTransactions`: the fee the transaction would pay
+/// and, if its `__execute__` reverted, why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    pub fee: FeeEstimate,
+    pub revert_reason: Option<String>,
+}
+
+impl Simulation {
+    pub fn from_rpc_json(value: &Value) -> Result<Self> {
+        let fee = value.get("fee_estimation").context("Simulation has no fee_estimation")?;
+        let revert_reason = value.pointer("/transaction_trace/execute_invocation/revert_reason")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        Ok(Simulation { fee: FeeEstimate::from_rpc_json(fee)?, revert_reason })
+    }
+}
+
+/// `value * multiplier`, rounded up; float casts saturate rather than wrap.
+fn scale(value: u128, multiplier: f64) -> u128 {
+    (value as f64 * multiplier).ceil() as u128
+}
+
+/// Who signs an invoke: the account key held in the vault, or a session key (which
+/// also enforces its policy and revocation list).
+pub enum InvokeSigner<'a> {
+    Vault { vault: &'a Vault, key_name: &'a str },
+    Session(&'a mut SessionKey),
+    /// A session key saved as vault entry `name`, whose spend totals are written back
+    /// after signing.
+    StoredSession { key: &'a mut SessionKey, vault: &'a mut Vault, name: &'a str },
+}
+
+impl InvokeSigner<'_> {
+    /// `[r, s]` over `tx.hash()`.
+    pub fn sign(&mut self, tx: &InvokeV3) -> Result<Vec<FieldElement>> {
+        let signature = match self {
+            InvokeSigner::Vault { vault, key_name } => vault.sign_hash(key_name, &tx.hash()?)?,
+            InvokeSigner::Session(key) => key.sign_invoke(tx)?,
+            InvokeSigner::StoredSession { key, vault, name } => key.sign_invoke_in_vault(tx, vault, name)?,
+        };
+        Ok(vec![signature.r, signature.s])
+    }
+}
+
+/// Cairo 1 account `__execute__` calldata: `[n, (to, selector, len, ...data)*]`.
+pub fn encode_calls(calls: &[Call]) -> Vec<FieldElement> {
+    let mut calldata = vec![FieldElement::from(calls.len() as u64)];
+    for call in calls {
+        calldata.push(call.to);
+        calldata.push(call.selector);
+        calldata.push(FieldElement::from(call.calldata.len() as u64));
+        calldata.extend_from_slice(&call.calldata);
+    }
+    calldata
+}
+
+/// Inverse of [`encode_calls`]. Truncated or trailing calldata is an error.
+pub fn decode_calls(calldata: &[FieldElement]) -> Result<Vec<Call>> {
+    let length = |felt: Option<&FieldElement>| -> Result<usize> {
+        let felt = felt.context("Truncated multicall calldata")?;
+        let value = u64::try_from(*felt).map_err(|_| anyhow::anyhow!("Invalid multicall length: {:#x}", felt))?;
+        Ok(usize::try_from(value)?)
+    };
+
+    let mut rest = calldata.iter();
+    let count = length(rest.next())?;
+    let mut calls = Vec::new();
+    for _ in 0..count {
+        let to = *rest.next().context("Truncated multicall calldata")?;
+        let selector = *rest.next().context("Truncated multicall calldata")?;
+        let len = length(rest.next())?;
+        let data: Vec<FieldElement> = rest.by_ref().take(len).copied().collect();
+        if data.len() != len {
+            anyhow::bail!("Truncated multicall calldata");
+        }
+        calls.push(Call { to, selector, calldata: data });
+    }
+    if rest.next().is_some() {
+        anyhow::bail!("Trailing felts after multicall calldata");
+    }
+    Ok(calls)
+}
+
+/// `name (60 bits) << 192 | max_amount (64 bits) << 128 | max_price_per_unit (128 bits)`.
+fn resource_bound_felt(name: &str, bound: &ResourceBound) -> Result<FieldElement> {
+    let mut bytes = [0u8; 32];
+    let name_felt = cairo_short_string_to_felt(name)?.to_bytes_be();
+    bytes[..8].copy_from_slice(&name_felt[24..]);
+    bytes[8..16].copy_from_slice(&bound.max_amount.to_be_bytes());
+    bytes[16..].copy_from_slice(&bound.max_price_per_unit.to_be_bytes());
+    Ok(FieldElement::from_bytes_be(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::core::utils::get_selector_from_name;
+
+    fn felt(hex: &str) -> FieldElement {
+        FieldElement::from_hex_be(hex).unwrap()
+    }
+
+    /// STRK transfer of 1000 from account 0x123 on Sepolia, nonce 7.
+    fn transfer(l1_data_gas: Option<ResourceBound>) -> InvokeV3 {
+        let call = Call {
+            to: felt("0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d"),
+            selector: get_selector_from_name("transfer").unwrap(),
+            calldata: vec![felt("0x456"), FieldElement::from(1000u64), FieldElement::ZERO],
+        };
+        let mut tx = InvokeV3::new(
+            felt("0x123"),
+            &[call],
+            FieldElement::from(7u64),
+            cairo_short_string_to_felt("SN_SEPOLIA").unwrap(),
+        );
+        tx.resource_bounds = ResourceBounds {
+            l1_gas: ResourceBound { max_amount: 0x100, max_price_per_unit: 0x2000 },
+            l2_gas: ResourceBound { max_amount: 0x300000, max_price_per_unit: 0x4000 },
+            l1_data_gas,
+        };
+        tx
+    }
+
+    #[test]
+    fn resource_bound_layout() {
+        let bound = ResourceBound { max_amount: 0x100, max_price_per_unit: 0x2000 };
+        assert_eq!(
+            resource_bound_felt("L1_GAS", &bound).unwrap(),
+            felt("0x00004c315f474153000000000000010000000000000000000000000000002000")
+        );
+        assert_eq!(
+            resource_bound_felt("L1_DATA", &bound).unwrap(),
+            felt("0x004c315f44415441000000000000010000000000000000000000000000002000")
+        );
+    }
+
+    #[test]
+    fn invoke_hash_with_l1_data_gas_matches_starknet_rs() {
+        // Reference values from starknet-accounts 0.16 `RawExecutionV3::transaction_hash`.
+        let mut tx = transfer(Some(ResourceBound { max_amount: 0x500, max_price_per_unit: 0x6000 }));
+        assert_eq!(
+            tx.hash().unwrap(),
+            felt("0x04683410fcf8919732891dc9724aea1ad472f6aac9949b43ee9a24451bcec90a")
+        );
+        tx.is_query = true;
+        assert_eq!(
+            tx.hash().unwrap(),
+            felt("0x079acb6394bc6ca88f46640b885bec41276fafa4038228ecbb4097d0d62c9be6")
+        );
+    }
+
+    #[test]
+    fn omitted_l1_data_gas_is_not_a_zero_bound() {
+        // Pre-0.13.4 form: the fee hash covers only tip, L1_GAS and L2_GAS.
+        assert_ne!(transfer(None).hash().unwrap(), transfer(Some(ResourceBound::default())).hash().unwrap());
+    }
+}
@@ -0,0 +1,258 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use starknet::core::types::FieldElement;
+use starknet::core::utils::{cairo_short_string_to_felt, get_selector_from_name, starknet_keccak};
+use starknet_crypto::poseidon_hash_many;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Domain struct name mandated by SNIP-12 revision 1.
+pub const DOMAIN_TYPE: &str = "StarknetDomain";
+/// Prefix shortstring of every SNIP-12 message hash.
+const MESSAGE_PREFIX: &str = "StarkNet Message";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+}
+
+/// SNIP-12 (revision 1) typed data in the JSON shape wallets and starknet.js use.
+/// Supported member types: `felt`, `shortstring`, `ContractAddress`, `ClassHash`,
+/// `selector`, `u128`, `timestamp`, `bool`, user-defined structs and `*` arrays of any
+/// of these. Revision 0, `string`, `enum`, `merkletree` and the preset types are not.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    pub types: BTreeMap<String, Vec<TypeField>>,
+    pub primary_type: String,
+    pub domain: Value,
+    pub message: Value,
+}
+
+impl TypedData {
+    pub fn from_json(json: &str) -> Result<Self> {
+        let typed: TypedData = serde_json::from_str(json).context("Invalid typed data JSON")?;
+        typed.check_revision()?;
+        Ok(typed)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    fn check_revision(&self) -> Result<()> {
+        if !self.types.contains_key(DOMAIN_TYPE) {
+            return Err(anyhow::anyhow!("Typed data has no {} type (only SNIP-12 revision 1 is supported)", DOMAIN_TYPE));
+        }
+        match self.domain.get("revision") {
+            Some(revision) if felt_from_value(revision)? == FieldElement::ONE => Ok(()),
+            _ => Err(anyhow::anyhow!("Only SNIP-12 revision 1 typed data is supported")),
+        }
+    }
+
+    /// `poseidon("StarkNet Message", domain hash, account, message hash)`, the value an
+    /// account signs.
+    pub fn message_hash(&self, account: FieldElement) -> Result<FieldElement> {
+        self.check_revision()?;
+        let prefix = cairo_short_string_to_felt(MESSAGE_PREFIX)?;
+        Ok(poseidon_hash_many(&[
+            prefix,
+            self.domain_hash()?,
+            account,
+            self.struct_hash(&self.primary_type, &self.message)?,
+        ]))
+    }
+
+    pub fn domain_hash(&self) -> Result<FieldElement> {
+        self.struct_hash(DOMAIN_TYPE, &self.domain)
+    }
+
+    /// `"Name"("field":"type",...)` for `name`, followed by every struct it references
+    /// in alphabetical order.
+    pub fn encode_type(&self, name: &str) -> Result<String> {
+        let mut dependencies = BTreeSet::new();
+        self.collect_dependencies(name, &mut dependencies)?;
+        dependencies.remove(name);
+
+        let mut encoded = self.encode_single_type(name)?;
+        for dependency in dependencies {
+            encoded.push_str(&self.encode_single_type(&dependency)?);
+        }
+        Ok(encoded)
+    }
+
+    pub fn type_hash(&self, name: &str) -> Result<FieldElement> {
+        Ok(starknet_keccak(self.encode_type(name)?.as_bytes()))
+    }
+
+    pub fn struct_hash(&self, name: &str, value: &Value) -> Result<FieldElement> {
+        let fields = self.fields(name)?;
+        let object = value.as_object().context(format!("Value for {} must be an object", name))?;
+
+        let mut elements = vec![self.type_hash(name)?];
+        for field in fields {
+            let member = object.get(&field.name)
+                .context(format!("Missing member '{}' of {}", field.name, name))?;
+            elements.push(self.encode_value(&field.field_type, member)
+                .context(format!("Invalid member '{}' of {}", field.name, name))?);
+        }
+        Ok(poseidon_hash_many(&elements))
+    }
+
+    fn fields(&self, name: &str) -> Result<&Vec<TypeField>> {
+        self.types.get(name).context(format!("Unknown type: {}", name))
+    }
+
+    fn encode_single_type(&self, name: &str) -> Result<String> {
+        let members: Vec<String> = self.fields(name)?
+            .iter()
+            .map(|field| format!("\"{}\":\"{}\"", field.name, field.field_type))
+            .collect();
+        Ok(format!("\"{}\"({})", name, members.join(",")))
+    }
+
+    fn collect_dependencies(&self, name: &str, found: &mut BTreeSet<String>) -> Result<()> {
+        if !found.insert(name.to_string()) {
+            return Ok(());
+        }
+        for field in self.fields(name)? {
+            let base = field.field_type.trim_end_matches('*');
+            if self.types.contains_key(base) {
+                self.collect_dependencies(base, found)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_value(&self, field_type: &str, value: &Value) -> Result<FieldElement> {
+        if let Some(element_type) = field_type.strip_suffix('*') {
+            let items = value.as_array().context("Expected an array")?;
+            let encoded = items.iter()
+                .map(|item| self.encode_value(element_type, item))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(poseidon_hash_many(&encoded));
+        }
+        if self.types.contains_key(field_type) {
+            return self.struct_hash(field_type, value);
+        }
+
+        match field_type {
+            "felt" | "shortstring" | "ContractAddress" | "ClassHash" => felt_from_value(value),
+            "u128" | "timestamp" => {
+                let felt = felt_from_value(value)?;
+                u128::try_from(felt).map_err(|_| anyhow::anyhow!("Value does not fit in u128"))?;
+                Ok(felt)
+            }
+            "bool" => match value {
+                Value::Bool(b) => Ok(if *b { FieldElement::ONE } else { FieldElement::ZERO }),
+                other => match felt_from_value(other)? {
+                    f if f == FieldElement::ZERO || f == FieldElement::ONE => Ok(f),
+                    _ => Err(anyhow::anyhow!("Expected a bool")),
+                },
+            },
+            "selector" => {
+                let name = value.as_str().context("Expected a selector name")?;
+                if name.starts_with("0x") {
+                    felt_from_value(value)
+                } else {
+                    Ok(get_selector_from_name(name)?)
+                }
+            }
+            other => Err(anyhow::anyhow!("Unsupported typed data type: {}", other)),
+        }
+    }
+}
+
+/// Same rules as starknet.js: `0x` hex, then decimal, otherwise a Cairo shortstring.
+fn felt_from_value(value: &Value) -> Result<FieldElement> {
+    match value {
+        Value::Number(n) => n.as_u64()
+            .map(FieldElement::from)
+            .context("Numeric value must be a non-negative integer"),
+        Value::String(s) if s.starts_with("0x") => {
+            FieldElement::from_hex_be(s).map_err(|_| anyhow::anyhow!("Invalid hex value: {}", s))
+        }
+        Value::String(s) if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) => {
+            FieldElement::from_dec_str(s).map_err(|_| anyhow::anyhow!("Invalid decimal value: {}", s))
+        }
+        Value::String(s) => Ok(cairo_short_string_to_felt(s)?),
+        _ => Err(anyhow::anyhow!("Expected a felt, got {}", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fixture of the starknet-rs / starknet.js revision 1 tests, with `Name` as a
+    /// `shortstring` and a few more basic members, since `string` is not supported.
+    const EXAMPLE: &str = r#"{
+      "types": {
+        "StarknetDomain": [
+          { "name": "name", "type": "shortstring" },
+          { "name": "version", "type": "shortstring" },
+          { "name": "chainId", "type": "shortstring" },
+          { "name": "revision", "type": "shortstring" }
+        ],
+        "Example Message": [
+          { "name": "Name", "type": "shortstring" },
+          { "name": "Some Array", "type": "u128*" },
+          { "name": "Some Object", "type": "My Object" },
+          { "name": "Bool", "type": "bool" },
+          { "name": "Classhash", "type": "ClassHash" },
+          { "name": "Timestamp", "type": "timestamp" }
+        ],
+        "My Object": [
+          { "name": "Some Selector", "type": "selector" },
+          { "name": "Some Contract Address", "type": "ContractAddress" }
+        ]
+      },
+      "primaryType": "Example Message",
+      "domain": { "name": "Starknet Example", "version": "1", "chainId": "SN_MAIN", "revision": "1" },
+      "message": {
+        "Name": "some name",
+        "Some Array": [1, 2, 3, 4],
+        "Some Object": { "Some Selector": "transfer", "Some Contract Address": "0x0123" },
+        "Bool": true,
+        "Classhash": "0x1234",
+        "Timestamp": 1234
+      }
+    }"#;
+
+    fn felt(hex: &str) -> FieldElement {
+        FieldElement::from_hex_be(hex).unwrap()
+    }
+
+    #[test]
+    fn domain_and_type_hashes_match_starknet_rs() {
+        let typed = TypedData::from_json(EXAMPLE).unwrap();
+        assert_eq!(
+            typed.domain_hash().unwrap(),
+            felt("0x03bfc3e1ff0f5c85c05bb8073a64a40b038eed00a449bc337c8cd2758f634640")
+        );
+        assert_eq!(
+            typed.type_hash("My Object").unwrap(),
+            felt("0x02f0ee9d399d4e7ccbc5d7e96df767296cc4b8a516600c121b393427ae3779f2")
+        );
+    }
+
+    #[test]
+    fn message_hash_matches_starknet_rs() {
+        // Reference value from starknet-core 0.16 `TypedData::message_hash`.
+        let typed = TypedData::from_json(EXAMPLE).unwrap();
+        assert_eq!(
+            typed.message_hash(felt("0x1234")).unwrap(),
+            felt("0x054f068efe9fcc5390e9837d735f73b37f99a8765bf697f83d1f7e183c055303")
+        );
+    }
+
+    #[test]
+    fn revision_0_is_rejected() {
+        let legacy = EXAMPLE.replace("StarknetDomain", "StarkNetDomain");
+        assert!(TypedData::from_json(&legacy).is_err());
+        let wrong_revision = EXAMPLE.replace(r#""revision": "1""#, r#""revision": "0""#);
+        assert!(TypedData::from_json(&wrong_revision).is_err());
+    }
+}
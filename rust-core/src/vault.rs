@@ -1,50 +1,1154 @@
 use aes_gcm::{
-    aead::{Aead, AeadCore, KeyInit, OsRng},
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
     Aes256Gcm, Key, Nonce
 };
 use anyhow::{Context, Result};
+use crate::audit::{AuditLog, AuditOp};
+use crate::secret::SecretString;
+use crate::shamir;
+use crate::state_file::unix_now;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use fs2::FileExt;
 use rand::RngCore;
-use std::fs;
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use starknet::core::crypto::Signature;
+use starknet::core::types::FieldElement;
+use starknet::signers::SigningKey;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use zeroize::{Zeroize, Zeroizing};
 
+/// Magic header that marks a versioned ciphertext envelope.
+const ENVELOPE_MAGIC: &[u8; 4] = b"SPRV";
+/// Current envelope format. Version 0 is the legacy bare `nonce + ciphertext` blob.
+const ENVELOPE_VERSION: u8 = 1;
+/// Header layout: magic(4) | version(1) | kdf id(1) | kdf params(12) | salt(16).
+const HEADER_LEN: usize = 4 + 1 + 1 + 12 + SALT_LEN;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+const KDF_ID_ARGON2ID: u8 = 1;
+/// Upper bounds on KDF parameters read from untrusted headers. argon2 itself accepts up
+/// to `u32::MAX` for memory and passes, enough to abort on allocation or hang forever.
+const MAX_M_COST_KIB: u32 = 1024 * 1024;
+const MAX_T_COST: u32 = 10;
+const MAX_P_COST: u32 = 8;
+
+/// Environment variables read by [`Vault::from_env`] (ADR-038 cloud mode).
+pub const VAULT_BLOB_ENV: &str = "STARK_VAULT_BLOB";
+pub const VAULT_PASSWORD_ENV: &str = "STARK_VAULT_PASSWORD";
+
+/// On-disk format version of `vault.bin`, the only one accepted. It binds each entry to
+/// its name and vault id as AES-GCM associated data.
+const FILE_VERSION: u8 = 2;
+
+/// Known plaintext sealed into `VaultFile::verifier` so a wrong password is caught at open.
+const VERIFIER_PLAINTEXT: &[u8] = b"stark_pyrust_chain vault verifier";
+/// Failed unlocks allowed before backoff starts, then 2s, 4s, 8s ... capped at one hour.
+const FREE_ATTEMPTS: u32 = 3;
+const BACKOFF_BASE_SECS: u64 = 2;
+const BACKOFF_MAX_SECS: u64 = 3600;
+/// How long an open waits for another process to release `<vault>.lock` before giving up
+/// with [`VaultError::Busy`].
+const LOCK_WAIT: Duration = if cfg!(test) { Duration::from_millis(200) } else { Duration::from_secs(30) };
+const LOCK_POLL: Duration = Duration::from_millis(50);
+
+/// Typed vault failures that callers may want to tell apart from I/O or format errors.
+#[derive(Debug, thiserror::Error)]
+pub enum VaultError {
+    /// AES-GCM rejected the entry: wrong password, or the ciphertext was edited, swapped in
+    /// from another entry, or copied from a different vault.
+    #[error("Secret '{name}' failed authentication (wrong password, or entry tampered with / swapped)")]
+    EntryAuthentication { name: String },
+    /// The password does not open the vault's verifier record.
+    #[error("Wrong vault password")]
+    WrongPassword,
+    /// Too many recent failed unlocks; see [`FailedAttempts`].
+    #[error("Vault locked after {failures} failed unlock attempts; retry in {retry_after_secs}s")]
+    LockedOut { failures: u32, retry_after_secs: u64 },
+    /// Another process held `<vault>.lock` for longer than [`LOCK_WAIT`].
+    #[error("Vault is in use by another process")]
+    Busy,
+}
+
+/// Key derivation settings recorded in every envelope header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "lowercase")]
+pub enum KdfParams {
+    /// Argon2id (RFC 9106). Memory cost is in KiB.
+    Argon2id { m_cost_kib: u32, t_cost: u32, p_cost: u32 },
+}
+
+impl Default for KdfParams {
+    /// OWASP baseline for Argon2id: 19 MiB, 2 passes, 1 lane.
+    fn default() -> Self {
+        KdfParams::Argon2id { m_cost_kib: 19 * 1024, t_cost: 2, p_cost: 1 }
+    }
+}
+
+impl KdfParams {
+    fn id(&self) -> u8 {
+        match self {
+            KdfParams::Argon2id { .. } => KDF_ID_ARGON2ID,
+        }
+    }
+
+    fn to_bytes(self) -> [u8; 12] {
+        let KdfParams::Argon2id { m_cost_kib, t_cost, p_cost } = self;
+        let mut out = [0u8; 12];
+        out[0..4].copy_from_slice(&m_cost_kib.to_le_bytes());
+        out[4..8].copy_from_slice(&t_cost.to_le_bytes());
+        out[8..12].copy_from_slice(&p_cost.to_le_bytes());
+        out
+    }
+
+    fn from_bytes(kdf_id: u8, bytes: &[u8]) -> Result<Self> {
+        let word = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+        let kdf = match kdf_id {
+            KDF_ID_ARGON2ID => KdfParams::Argon2id {
+                m_cost_kib: word(0),
+                t_cost: word(4),
+                p_cost: word(8),
+            },
+            other => return Err(anyhow::anyhow!("Unsupported KDF id: {}", other)),
+        };
+        kdf.check_bounds()?;
+        Ok(kdf)
+    }
+
+    /// Reject parameters beyond [`MAX_M_COST_KIB`], [`MAX_T_COST`] or [`MAX_P_COST`]
+    /// before they reach Argon2.
+    fn check_bounds(&self) -> Result<()> {
+        let KdfParams::Argon2id { m_cost_kib, t_cost, p_cost } = *self;
+        if m_cost_kib > MAX_M_COST_KIB || t_cost > MAX_T_COST || p_cost > MAX_P_COST {
+            return Err(anyhow::anyhow!(
+                "Argon2 parameters out of bounds (m={} KiB, t={}, p={}; max m={} KiB, t={}, p={})",
+                m_cost_kib, t_cost, p_cost, MAX_M_COST_KIB, MAX_T_COST, MAX_P_COST
+            ));
+        }
+        Ok(())
+    }
+
+    /// Derive a 256-bit AES key from `password` and `salt`.
+    fn derive_key(&self, password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+        self.check_bounds()?;
+        let KdfParams::Argon2id { m_cost_kib, t_cost, p_cost } = *self;
+        let params = Params::new(m_cost_kib, t_cost, p_cost, Some(32))
+            .map_err(|e| anyhow::anyhow!("Invalid Argon2 parameters: {}", e))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password.as_bytes(), salt, key.as_mut())
+            .map_err(|e| anyhow::anyhow!("Key derivation failure: {}", e))?;
+        Ok(key)
+    }
+}
+
+/// Parsed envelope header. The raw header bytes are authenticated as AES-GCM associated
+/// data, so tampering with the KDF parameters or salt fails decryption.
+struct EnvelopeHeader {
+    kdf: KdfParams,
+    salt: [u8; SALT_LEN],
+}
+
+impl EnvelopeHeader {
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_LEN);
+        out.extend_from_slice(ENVELOPE_MAGIC);
+        out.push(ENVELOPE_VERSION);
+        out.push(self.kdf.id());
+        out.extend_from_slice(&self.kdf.to_bytes());
+        out.extend_from_slice(&self.salt);
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_LEN {
+            return Err(anyhow::anyhow!("Envelope header truncated"));
+        }
+        let version = bytes[4];
+        if version != ENVELOPE_VERSION {
+            return Err(anyhow::anyhow!("Unsupported envelope version: {}", version));
+        }
+        let kdf = KdfParams::from_bytes(bytes[5], &bytes[6..18])?;
+        let mut salt = [0u8; SALT_LEN];
+        salt.copy_from_slice(&bytes[18..HEADER_LEN]);
+        Ok(EnvelopeHeader { kdf, salt })
+    }
+}
+
+/// Serialized layout of `vault.bin`. Entry values are hex `nonce + ciphertext` blobs
+/// sealed with the key derived from `kdf` and `salt`.
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    magic: String,
+    version: u8,
+    vault_id: String,
+    kdf: KdfParams,
+    salt: String,
+    /// Sealed [`VERIFIER_PLAINTEXT`]; absent in files written before it existed and
+    /// added on the next successful open.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    verifier: Option<String>,
+    entries: BTreeMap<String, String>,
+}
+
+impl VaultFile {
+    fn read(path: &Path) -> Result<Self> {
+        let raw = fs::read(path).context(format!("Failed to read vault file: {}", path.display()))?;
+        Self::parse(&raw).context(format!("Invalid vault file: {}", path.display()))
+    }
+
+    fn parse(raw: &[u8]) -> Result<Self> {
+        let file: VaultFile = serde_json::from_slice(raw).context("Corrupt vault file")?;
+        if file.magic.as_bytes() != ENVELOPE_MAGIC {
+            return Err(anyhow::anyhow!("Not a vault file"));
+        }
+        if file.version != FILE_VERSION {
+            return Err(anyhow::anyhow!("Unsupported vault file version: {}", file.version));
+        }
+        file.kdf.check_bounds()?;
+        Ok(file)
+    }
+
+    /// Write via a temp file + rename so a crash never leaves a half-written vault. The
+    /// file is created owner-only (0600 on unix) and the directory is synced after the
+    /// rename, so the new file survives a crash.
+    fn write_atomic(&self, path: &Path) -> Result<()> {
+        let tmp_path = sidecar_path(path, "tmp");
+        let json = serde_json::to_vec_pretty(self)?;
+        {
+            let mut tmp = create_private(&tmp_path).context("Failed to create vault temp file")?;
+            tmp.write_all(&json)?;
+            tmp.sync_all()?;
+        }
+        fs::rename(&tmp_path, path).context("Failed to replace vault file")?;
+        sync_parent_dir(path)
+    }
+
+    fn salt_bytes(&self) -> Result<[u8; SALT_LEN]> {
+        let bytes = hex::decode(&self.salt).context("Invalid vault salt")?;
+        bytes.try_into().map_err(|_| anyhow::anyhow!("Invalid vault salt length"))
+    }
+
+    /// Whether `cipher` is the key this file was sealed with. Files without a verifier
+    /// fall back to trial-decrypting one entry; an empty legacy file accepts any password.
+    fn password_matches(&self, cipher: &Aes256Gcm) -> Result<bool> {
+        if let Some(verifier) = &self.verifier {
+            return Ok(open_sealed(cipher, verifier, &verifier_aad(&self.vault_id))?.is_some());
+        }
+        match self.entries.iter().next() {
+            Some((name, sealed)) => Ok(open_sealed(cipher, sealed, &entry_aad(&self.vault_id, name))?.is_some()),
+            None => Ok(true),
+        }
+    }
+}
+
+/// Persistent failed-unlock counter in `<vault>.attempts`. After [`FREE_ATTEMPTS`]
+/// failures each further attempt must wait an exponentially growing delay, which
+/// survives process restarts. It only slows guessing through this code; a copied
+/// `vault.bin` is still protected by the Argon2id cost alone.
+#[derive(Default, Serialize, Deserialize)]
+struct FailedAttempts {
+    failures: u32,
+    last_failure: u64,
+}
+
+impl FailedAttempts {
+    fn load(path: &Path) -> Result<Self> {
+        match fs::read(path) {
+            Ok(raw) => serde_json::from_slice(&raw).context("Corrupt vault attempts file"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).context("Failed to read vault attempts file"),
+        }
+    }
+
+    fn backoff_secs(&self) -> u64 {
+        if self.failures < FREE_ATTEMPTS {
+            return 0;
+        }
+        let doublings = (self.failures - FREE_ATTEMPTS).min(32);
+        BACKOFF_BASE_SECS.saturating_mul(1u64 << doublings).min(BACKOFF_MAX_SECS)
+    }
+
+    /// Fail with [`VaultError::LockedOut`] while the current backoff has not elapsed.
+    fn check(path: &Path) -> Result<()> {
+        let attempts = Self::load(path)?;
+        let ready_at = attempts.last_failure.saturating_add(attempts.backoff_secs());
+        let now = unix_now()?;
+        if now < ready_at {
+            return Err(VaultError::LockedOut {
+                failures: attempts.failures,
+                retry_after_secs: ready_at - now,
+            }.into());
+        }
+        Ok(())
+    }
+
+    fn record_failure(path: &Path) -> Result<()> {
+        let mut attempts = Self::load(path)?;
+        attempts.failures = attempts.failures.saturating_add(1);
+        attempts.last_failure = unix_now()?;
+        fs::write(path, serde_json::to_vec(&attempts)?).context("Failed to write vault attempts file")
+    }
+
+    fn reset(path: &Path) -> Result<()> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e).context("Failed to reset vault attempts file"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Create (or truncate) `path` readable and writable by its owner only. A leftover file
+/// is removed first, since the mode only applies to newly created files.
+fn create_private(path: &Path) -> std::io::Result<File> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)
+}
+
+/// fsync the directory holding `path`, making a rename into it durable.
+fn sync_parent_dir(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        File::open(dir)
+            .and_then(|d| d.sync_all())
+            .context(format!("Failed to sync directory {}", dir.display()))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Failed-attempt counter for a blob unlocked with [`Vault::from_blob`], kept in the
+/// temp directory under a hash of the blob's vault id. Editing the id to get a fresh
+/// counter gains nothing: the id is bound into the verifier, so no password opens the
+/// edited blob.
+fn blob_attempts_path(vault_id: &str) -> PathBuf {
+    let tag = hex::encode(&blake3::hash(vault_id.as_bytes()).as_bytes()[..8]);
+    env::temp_dir().join(format!("stark_vault_blob_{}.attempts", tag))
+}
+
+/// `vault.bin` -> `vault.bin.<suffix>`
+fn sidecar_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Exclusive advisory lock on `<vault>.lock`, held for the lifetime of the guard.
+/// Serializes read-modify-write cycles between overlapping pulses; a process that cannot
+/// get it within [`LOCK_WAIT`] is refused rather than queued behind a stuck holder.
+struct VaultLock {
+    file: File,
+}
+
+impl VaultLock {
+    fn acquire(vault_path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(sidecar_path(vault_path, "lock"))
+            .context("Failed to open vault lock file")?;
+        let deadline = Instant::now() + LOCK_WAIT;
+        loop {
+            match file.try_lock_exclusive() {
+                Ok(()) => return Ok(VaultLock { file }),
+                Err(e) if e.kind() == fs2::lock_contended_error().kind() && Instant::now() < deadline => {
+                    std::thread::sleep(LOCK_POLL);
+                }
+                Err(e) if e.kind() == fs2::lock_contended_error().kind() => return Err(VaultError::Busy.into()),
+                Err(e) => return Err(e).context("Failed to lock vault"),
+            }
+        }
+    }
+}
+
+impl Drop for VaultLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+fn cipher_for(kdf: &KdfParams, password: &str, salt: &[u8]) -> Result<Aes256Gcm> {
+    let key_bytes = kdf.derive_key(password, salt)?;
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key_bytes.as_ref())))
+}
+
+/// Move decrypted bytes into a [`SecretString`], wiping them if they are not UTF-8.
+fn into_secret(mut bytes: Zeroizing<Vec<u8>>) -> Result<SecretString> {
+    String::from_utf8(std::mem::take(&mut *bytes))
+        .map(SecretString::new)
+        .map_err(|e| {
+            e.into_bytes().zeroize();
+            anyhow::anyhow!("Invalid UTF-8")
+        })
+}
+
+/// Associated data for a store entry: magic, file format version, vault id and entry
+/// name. A ciphertext moved to another name or another vault no longer authenticates.
+fn entry_aad(vault_id: &str, name: &str) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(ENVELOPE_MAGIC.len() + 2 + vault_id.len() + name.len());
+    aad.extend_from_slice(ENVELOPE_MAGIC);
+    aad.push(FILE_VERSION);
+    aad.extend_from_slice(vault_id.as_bytes());
+    aad.push(0);
+    aad.extend_from_slice(name.as_bytes());
+    aad
+}
+
+/// Associated data for the verifier record. `0xff` cannot start a UTF-8 entry name, so
+/// the verifier never collides with an entry's AAD.
+fn verifier_aad(vault_id: &str) -> Vec<u8> {
+    let mut aad = entry_aad(vault_id, "");
+    aad.push(0xff);
+    aad
+}
+
+fn seal_verifier(cipher: &Aes256Gcm, vault_id: &str) -> Result<String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, Payload { msg: VERIFIER_PLAINTEXT, aad: &verifier_aad(vault_id) })
+        .map_err(|e| anyhow::anyhow!("Encryption failure: {}", e))?;
+
+    let mut combined = nonce.to_vec();
+    combined.extend(ciphertext);
+    Ok(hex::encode(combined))
+}
+
+/// Seal a store entry as hex `nonce + ciphertext` under the file-level key.
+fn seal_entry(cipher: &Aes256Gcm, vault_id: &str, name: &str, plaintext: &str) -> Result<String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let aad = entry_aad(vault_id, name);
+    let ciphertext = cipher.encrypt(&nonce, Payload { msg: plaintext.as_bytes(), aad: &aad })
+        .map_err(|e| anyhow::anyhow!("Encryption failure: {}", e))?;
+
+    let mut combined = nonce.to_vec();
+    combined.extend(ciphertext);
+    Ok(hex::encode(combined))
+}
+
+fn open_entry(cipher: &Aes256Gcm, vault_id: &str, name: &str, sealed_hex: &str) -> Result<SecretString> {
+    open_sealed(cipher, sealed_hex, &entry_aad(vault_id, name))?
+        .ok_or_else(|| VaultError::EntryAuthentication { name: name.to_string() }.into())
+        .and_then(into_secret)
+}
+
+/// Decrypt a hex `nonce + ciphertext` blob. `Ok(None)` means AES-GCM authentication failed.
+/// The plaintext is wiped when dropped.
+fn open_sealed(cipher: &Aes256Gcm, sealed_hex: &str, aad: &[u8]) -> Result<Option<Zeroizing<Vec<u8>>>> {
+    let bytes = hex::decode(sealed_hex).context("Invalid hex string")?;
+    if bytes.len() < NONCE_LEN {
+        return Err(anyhow::anyhow!("Ciphertext too short"));
+    }
+    let (nonce_bytes, ciphertext_bytes) = bytes.split_at(NONCE_LEN);
+    Ok(cipher.decrypt(Nonce::from_slice(nonce_bytes), Payload { msg: ciphertext_bytes, aad }).ok().map(Zeroizing::new))
+}
+
+/// Entries of a vault opened with [`Vault::open`] or [`Vault::from_env`].
+/// `path` is `None` for an environment-injected vault, which is read-only.
+struct SecretStore {
+    path: Option<PathBuf>,
+    vault_id: String,
+    entries: BTreeMap<String, String>,
+}
+
+impl SecretStore {
+    fn writable_path(&self) -> Result<PathBuf> {
+        self.path.clone()
+            .context("Vault was loaded from the environment and is read-only")
+    }
+}
+
+/// The AES key schedule inside `cipher` is wiped on drop (`aes/zeroize`), as is the
+/// retained password.
 pub struct Vault {
+    password: SecretString,
+    header: EnvelopeHeader,
     cipher: Aes256Gcm,
+    store: Option<SecretStore>,
+    audit: Option<AuditLog>,
 }
 
 impl Vault {
+    /// Create a vault keyed with the default Argon2id parameters and a fresh random salt.
     pub fn new(password: &str) -> Result<Self> {
-        // In a production system, use PBKDF2 or Argon2 for key derivation.
-        // For this implementation, we'll hash the password to 32 bytes (256 bits).
-        let key_bytes = blake3::hash(password.as_bytes());
-        let key = Key::<Aes256Gcm>::from_slice(key_bytes.as_bytes());
-        let cipher = Aes256Gcm::new(key);
-        Ok(Vault { cipher })
+        Self::with_kdf(password, KdfParams::default())
+    }
+
+    /// Create a vault with explicit KDF parameters (e.g. a cheaper profile for CI runners).
+    pub fn with_kdf(password: &str, kdf: KdfParams) -> Result<Self> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self::from_salt(password, kdf, salt)
+    }
+
+    fn from_salt(password: &str, kdf: KdfParams, salt: [u8; SALT_LEN]) -> Result<Self> {
+        let cipher = cipher_for(&kdf, password, &salt)?;
+
+        Ok(Vault {
+            password: SecretString::from(password),
+            header: EnvelopeHeader { kdf, salt },
+            cipher,
+            store: None,
+            audit: None,
+        })
+    }
+
+    /// Open the named-secret store at `path` (e.g. `vault.bin`), creating an empty one if
+    /// the file does not exist yet.
+    ///
+    /// A wrong password fails with [`VaultError::WrongPassword`] and counts towards the
+    /// backoff in `<vault>.attempts`; while it is active, opens fail with
+    /// [`VaultError::LockedOut`] before any key derivation is done.
+    pub fn open<P: AsRef<Path>>(path: P, password: &str) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let _lock = VaultLock::acquire(&path)?;
+
+        if path.exists() {
+            let attempts_path = sidecar_path(&path, "attempts");
+            FailedAttempts::check(&attempts_path)?;
+
+            let mut file = VaultFile::read(&path)?;
+            let mut vault = Self::from_salt(password, file.kdf, file.salt_bytes()?)?;
+            if !file.password_matches(&vault.cipher)? {
+                return Err(Self::reject_password(&path));
+            }
+            FailedAttempts::reset(&attempts_path)?;
+
+            if file.verifier.is_none() {
+                file.verifier = Some(seal_verifier(&vault.cipher, &file.vault_id)?);
+                file.write_atomic(&path)?;
+            }
+            vault.store = Some(SecretStore { path: Some(path.clone()), vault_id: file.vault_id, entries: file.entries });
+            vault.attach_audit_log(&path)?;
+            return Ok(vault);
+        }
+
+        let mut vault = Self::new(password)?;
+        let mut id_bytes = [0u8; 16];
+        OsRng.fill_bytes(&mut id_bytes);
+        let vault_id = hex::encode(id_bytes);
+        let file = VaultFile {
+            magic: String::from_utf8_lossy(ENVELOPE_MAGIC).into_owned(),
+            version: FILE_VERSION,
+            verifier: Some(seal_verifier(&vault.cipher, &vault_id)?),
+            vault_id,
+            kdf: vault.header.kdf,
+            salt: hex::encode(vault.header.salt),
+            entries: BTreeMap::new(),
+        };
+        file.write_atomic(&path)?;
+        vault.store = Some(SecretStore { path: Some(path.clone()), vault_id: file.vault_id, entries: file.entries });
+        vault.attach_audit_log(&path)?;
+        Ok(vault)
+    }
+
+    /// Count a failed unlock against the vault at `path` and log it. The bookkeeping is
+    /// best-effort: the caller always gets [`VaultError::WrongPassword`].
+    fn reject_password(path: &Path) -> anyhow::Error {
+        let _ = FailedAttempts::record_failure(&sidecar_path(path, "attempts"));
+        let _ = AuditLog::new(sidecar_path(path, "audit")).append(AuditOp::UnlockFailed, None);
+        VaultError::WrongPassword.into()
+    }
+
+    /// Every access to a file-backed vault is recorded in `<vault>.audit`
+    /// (see [`crate::audit::verify_chain`]).
+    fn attach_audit_log(&mut self, vault_path: &Path) -> Result<()> {
+        let log = AuditLog::new(sidecar_path(vault_path, "audit"));
+        log.append(AuditOp::Unlock, None)?;
+        self.audit = Some(log);
+        Ok(())
     }
 
+    fn record(&self, op: AuditOp, secret: Option<&str>) -> Result<()> {
+        match &self.audit {
+            Some(log) => log.append(op, secret),
+            None => Ok(()),
+        }
+    }
+
+    /// Open the store at `path` with a password rebuilt from Shamir shares
+    /// (see [`shamir::split_password`]) instead of typing it.
+    pub fn open_with_shares<P: AsRef<Path>, S: AsRef<str>>(path: P, shares: &[S]) -> Result<Self> {
+        let password = shamir::recover_password(shares)?;
+        Self::open(path, &password)
+    }
+
+    /// Load a whole encrypted vault from the environment: `STARK_VAULT_BLOB` holds the
+    /// base64 of a `vault.bin`, `STARK_VAULT_PASSWORD` unlocks it. Lets CI inject one
+    /// sealed blob instead of exposing raw keys as individual env vars.
+    pub fn from_env() -> Result<Self> {
+        Self::from_env_vars(VAULT_BLOB_ENV, VAULT_PASSWORD_ENV)
+    }
+
+    pub fn from_env_vars(blob_var: &str, password_var: &str) -> Result<Self> {
+        let blob = env::var(blob_var).context(format!("{} is not set", blob_var))?;
+        let password = Zeroizing::new(env::var(password_var).context(format!("{} is not set", password_var))?);
+        Self::from_blob(blob.trim(), &password)
+    }
+
+    /// Unlock a base64 vault blob (as produced by [`Vault::export_blob`]) in memory.
+    /// Wrong passwords back off like [`Vault::open`], counted per vault id in the temp
+    /// directory (see [`blob_attempts_path`]).
+    pub fn from_blob(blob_b64: &str, password: &str) -> Result<Self> {
+        let raw = BASE64.decode(blob_b64).context("Vault blob is not valid base64")?;
+        let file = VaultFile::parse(&raw).context("Invalid vault blob")?;
+        let attempts_path = blob_attempts_path(&file.vault_id);
+        FailedAttempts::check(&attempts_path)?;
+        let mut vault = Self::from_salt(password, file.kdf, file.salt_bytes()?)?;
+        if !file.password_matches(&vault.cipher)? {
+            let _ = FailedAttempts::record_failure(&attempts_path);
+            return Err(VaultError::WrongPassword.into());
+        }
+        FailedAttempts::reset(&attempts_path)?;
+        vault.store = Some(SecretStore { path: None, vault_id: file.vault_id, entries: file.entries });
+        Ok(vault)
+    }
+
+    /// Base64 of the vault file, ready to paste into a CI secret such as `STARK_VAULT_BLOB`.
+    /// Entries stay encrypted; the password must be supplied separately.
+    pub fn export_blob(&self) -> Result<String> {
+        let path = self.secret_store()?.writable_path()?;
+        let _lock = VaultLock::acquire(&path)?;
+        let raw = fs::read(&path).context("Failed to read vault file")?;
+        VaultFile::parse(&raw)?;
+        self.record(AuditOp::ExportBlob, None)?;
+        Ok(BASE64.encode(raw))
+    }
+
+    /// Decrypt a stored secret. Returns `None` if no entry has that name.
+    /// A swapped, renamed or edited entry fails with [`VaultError::EntryAuthentication`].
+    pub fn get_secret(&self, name: &str) -> Result<Option<SecretString>> {
+        let store = self.secret_store()?;
+        match store.entries.get(name) {
+            Some(sealed) => {
+                match open_entry(&self.cipher, &store.vault_id, name, sealed) {
+                    Ok(secret) => {
+                        self.record(AuditOp::Read, Some(name))?;
+                        Ok(Some(secret))
+                    }
+                    Err(e) => {
+                        self.record(AuditOp::DecryptFailed, Some(name))?;
+                        Err(e)
+                    }
+                }
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Encrypt and persist a secret, replacing any existing entry with the same name.
+    pub fn set_secret(&mut self, name: &str, value: &str) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(anyhow::anyhow!("Secret name must not be empty"));
+        }
+        let sealed = seal_entry(&self.cipher, &self.secret_store()?.vault_id, name, value)?;
+        self.update_store(|entries| {
+            entries.insert(name.to_string(), sealed);
+            Ok(())
+        })?;
+        self.record(AuditOp::Write, Some(name))
+    }
+
+    /// Read-modify-write one secret under the vault lock, so processes updating the same
+    /// entry never lose each other's changes. `f` gets the value currently on disk
+    /// (`None` if absent) and returns the new value; nothing is written if it fails.
+    pub fn update_secret<T>(
+        &mut self,
+        name: &str,
+        f: impl FnOnce(Option<&str>) -> Result<(Zeroizing<String>, T)>,
+    ) -> Result<T> {
+        if name.trim().is_empty() {
+            return Err(anyhow::anyhow!("Secret name must not be empty"));
+        }
+        let cipher = self.cipher.clone();
+        let vault_id = self.secret_store()?.vault_id.clone();
+        let out = self.update_store(|entries| {
+            let current = entries.get(name)
+                .map(|sealed| open_entry(&cipher, &vault_id, name, sealed))
+                .transpose()?;
+            let (value, out) = f(current.as_ref().map(|secret| secret.expose_secret()))?;
+            entries.insert(name.to_string(), seal_entry(&cipher, &vault_id, name, &value)?);
+            Ok(out)
+        })?;
+        self.record(AuditOp::Write, Some(name))?;
+        Ok(out)
+    }
+
+    /// Remove a secret. Returns `false` if it did not exist.
+    pub fn delete_secret(&mut self, name: &str) -> Result<bool> {
+        let existed = self.update_store(|entries| Ok(entries.remove(name).is_some()))?;
+        self.record(AuditOp::Delete, Some(name))?;
+        Ok(existed)
+    }
+
+    /// Names of all stored secrets, sorted.
+    pub fn list_secrets(&self) -> Result<Vec<String>> {
+        Ok(self.secret_store()?.entries.keys().cloned().collect())
+    }
+
+    /// Rotate the vault password: every entry is re-encrypted under a key derived from
+    /// `new_password` and a fresh salt, with `kdf` or, when `None`, the vault's current
+    /// Argon2 parameters. All entries must decrypt with `old_password` and re-verify under
+    /// the new key before anything is written.
+    ///
+    /// The previous file is copied to `<vault>.bak` for the swap, an atomic rename. The
+    /// new file is then read back and checked, and the backup is deleted, so no copy
+    /// that opens with the old password is left behind. If anything fails before that,
+    /// the backup is moved back and the vault is left as it was, under `old_password`.
+    /// Blobs exported earlier with [`Vault::export_blob`] still open with the old
+    /// password and must be re-exported.
+    pub fn rekey(&mut self, old_password: &str, new_password: &str, kdf: Option<KdfParams>) -> Result<()> {
+        if new_password.is_empty() {
+            return Err(anyhow::anyhow!("New vault password must not be empty"));
+        }
+        let store = self.store.as_mut()
+            .context("Vault has no backing file (use Vault::open)")?;
+        let path = store.writable_path()?;
+        let _lock = VaultLock::acquire(&path)?;
+        FailedAttempts::check(&sidecar_path(&path, "attempts"))?;
+
+        let file = VaultFile::read(&path)?;
+        if file.vault_id != store.vault_id {
+            return Err(anyhow::anyhow!("Vault file was replaced by another process; reopen it"));
+        }
+
+        let old_cipher = cipher_for(&file.kdf, old_password, &file.salt_bytes()?)?;
+        if !file.password_matches(&old_cipher)? {
+            return Err(Self::reject_password(&path));
+        }
+        let mut plaintexts = BTreeMap::new();
+        for (name, sealed) in &file.entries {
+            let plaintext = open_entry(&old_cipher, &file.vault_id, name, sealed)
+                .context(format!("Secret '{}' does not decrypt with the old password; aborting re-key", name))?;
+            plaintexts.insert(name.clone(), plaintext);
+        }
+
+        let new_kdf = kdf.unwrap_or(file.kdf);
+        let mut new_salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut new_salt);
+        let new_cipher = cipher_for(&new_kdf, new_password, &new_salt)?;
+
+        let mut new_entries = BTreeMap::new();
+        for (name, plaintext) in &plaintexts {
+            let sealed = seal_entry(&new_cipher, &file.vault_id, name, plaintext.expose_secret())?;
+            if open_entry(&new_cipher, &file.vault_id, name, &sealed)?.expose_secret() != plaintext.expose_secret() {
+                return Err(anyhow::anyhow!("Re-encryption check failed for '{}'; aborting re-key", name));
+            }
+            new_entries.insert(name.clone(), sealed);
+        }
+
+        let new_file = VaultFile {
+            kdf: new_kdf,
+            salt: hex::encode(new_salt),
+            verifier: Some(seal_verifier(&new_cipher, &file.vault_id)?),
+            entries: new_entries,
+            ..file
+        };
+
+        let backup_path = sidecar_path(&path, "bak");
+        fs::copy(&path, &backup_path).context("Failed to write vault backup")?;
+        File::open(&backup_path)?.sync_all()?;
+        let swapped = new_file.write_atomic(&path)
+            .and_then(|_| Self::check_rekeyed(&path, &new_file, &new_cipher, &plaintexts));
+        if let Err(e) = swapped {
+            fs::rename(&backup_path, &path)
+                .context(format!("Re-key failed ({:#}) and restoring {} failed", e, backup_path.display()))?;
+            sync_parent_dir(&path)?;
+            return Err(e.context("Re-key failed; vault restored under the old password"));
+        }
+        fs::remove_file(&backup_path).context("Failed to delete vault backup")?;
+        sync_parent_dir(&path)?;
+
+        store.entries = new_file.entries;
+        self.password = SecretString::from(new_password);
+        self.header = EnvelopeHeader { kdf: new_kdf, salt: new_salt };
+        self.cipher = new_cipher;
+        self.record(AuditOp::Rekey, None)
+    }
+
+    /// Read back a re-keyed file and check every entry opens to its old plaintext.
+    fn check_rekeyed(
+        path: &Path,
+        expected: &VaultFile,
+        cipher: &Aes256Gcm,
+        plaintexts: &BTreeMap<String, SecretString>,
+    ) -> Result<()> {
+        let written = VaultFile::read(path)?;
+        if written.salt != expected.salt || written.kdf != expected.kdf || !written.password_matches(cipher)? {
+            return Err(anyhow::anyhow!("Re-keyed vault does not open with the new password"));
+        }
+        if written.entries.len() != plaintexts.len() {
+            return Err(anyhow::anyhow!("Re-keyed vault lost entries"));
+        }
+        for (name, plaintext) in plaintexts {
+            let sealed = written.entries.get(name).context(format!("Re-keyed vault lost '{}'", name))?;
+            if open_entry(cipher, &written.vault_id, name, sealed)?.expose_secret() != plaintext.expose_secret() {
+                return Err(anyhow::anyhow!("Re-keyed entry '{}' does not match", name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Import a Web3 Secret Storage keystore as written by starkli / starknet-rs
+    /// (scrypt + aes-128-ctr) and store the signing key as entry `name`.
+    /// Returns the matching Stark public key.
+    pub fn import_keystore<P: AsRef<Path>>(&mut self, keystore_path: P, keystore_password: &str, name: &str) -> Result<String> {
+        let signing_key = SigningKey::from_keystore(keystore_path.as_ref(), keystore_password)
+            .map_err(|e| anyhow::anyhow!("Failed to decrypt keystore {}: {}", keystore_path.as_ref().display(), e))?;
+        let private_key = Zeroizing::new(format!("{:#064x}", signing_key.secret_scalar()));
+        self.set_secret(name, &private_key)?;
+        Ok(format!("{:#064x}", signing_key.verifying_key().scalar()))
+    }
+
+    /// Export the signing key stored as `name` to a new keystore file. The key is only
+    /// ever written re-encrypted under `keystore_password`.
+    pub fn export_keystore<P: AsRef<Path>>(&self, name: &str, keystore_path: P, keystore_password: &str) -> Result<()> {
+        let keystore_path = keystore_path.as_ref();
+        if keystore_path.exists() {
+            return Err(anyhow::anyhow!("Refusing to overwrite existing keystore: {}", keystore_path.display()));
+        }
+        self.signing_key(name)?
+            .save_as_keystore(keystore_path, keystore_password)
+            .map_err(|e| anyhow::anyhow!("Failed to write keystore: {}", e))
+    }
+
+    /// Sign `hash` (e.g. a SNIP-12 message hash) with the Stark key stored as `name`,
+    /// without the key ever leaving the vault.
+    pub fn sign_hash(&self, name: &str, hash: &FieldElement) -> Result<Signature> {
+        self.signing_key(name)?
+            .sign(hash)
+            .context(format!("Failed to sign with '{}'", name))
+    }
+
+    fn signing_key(&self, name: &str) -> Result<SigningKey> {
+        let private_key = self.get_secret(name)?
+            .ok_or_else(|| anyhow::anyhow!("Secret not found: {}", name))?;
+        let scalar = FieldElement::from_hex_be(private_key.expose_secret().trim())
+            .context(format!("Secret '{}' is not a Stark private key", name))?;
+        Ok(SigningKey::from_secret_scalar(scalar))
+    }
+
+    fn secret_store(&self) -> Result<&SecretStore> {
+        self.store.as_ref()
+            .context("Vault has no backing file (use Vault::open)")
+    }
+
+    /// Lock the file, re-read it so concurrent writers are not clobbered, apply `f`,
+    /// then write the result back atomically. Nothing is written if `f` fails.
+    fn update_store<T>(&mut self, f: impl FnOnce(&mut BTreeMap<String, String>) -> Result<T>) -> Result<T> {
+        let salt_hex = hex::encode(self.header.salt);
+        let store = self.store.as_mut()
+            .context("Vault has no backing file (use Vault::open)")?;
+        let path = store.writable_path()?;
+        let _lock = VaultLock::acquire(&path)?;
+
+        let mut file = VaultFile::read(&path)?;
+        if file.vault_id != store.vault_id || file.salt != salt_hex {
+            return Err(anyhow::anyhow!("Vault file was replaced or re-keyed by another process; reopen it"));
+        }
+
+        let out = f(&mut file.entries)?;
+        file.write_atomic(&path)?;
+        store.entries = file.entries;
+        Ok(out)
+    }
+
+    /// Encrypt into a v1 envelope: `header | nonce | ciphertext`, hex encoded.
     pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let header = self.header.encode();
         let nonce = Aes256Gcm::generate_nonce(&mut OsRng); // 96-bits; unique per message
-        let ciphertext = self.cipher.encrypt(&nonce, plaintext.as_bytes())
+        let ciphertext = self.cipher
+            .encrypt(&nonce, Payload { msg: plaintext.as_bytes(), aad: &header })
             .map_err(|e| anyhow::anyhow!("Encryption failure: {}", e))?;
-        
-        // Return nonce + ciphertext as hex string
-        let mut combined = nonce.to_vec();
+
+        let mut combined = header;
+        combined.extend_from_slice(&nonce);
         combined.extend(ciphertext);
         Ok(hex::encode(combined))
     }
 
-    pub fn decrypt(&self, encrypted_hex: &str) -> Result<String> {
+    /// Decrypt either a v1 envelope or a v0 legacy blob (bare `nonce + ciphertext`
+    /// keyed with an unsalted blake3 hash of the password).
+    pub fn decrypt(&self, encrypted_hex: &str) -> Result<SecretString> {
         let encrypted_bytes = hex::decode(encrypted_hex).context("Invalid hex string")?;
-        
-        if encrypted_bytes.len() < 12 {
+
+        let plaintext_bytes = if encrypted_bytes.starts_with(ENVELOPE_MAGIC) {
+            // A legacy nonce can start with the magic bytes by chance, so fall back to v0.
+            self.decrypt_envelope(&encrypted_bytes)
+                .or_else(|e| self.decrypt_legacy(&encrypted_bytes).map_err(|_| e))?
+        } else {
+            self.decrypt_legacy(&encrypted_bytes)?
+        };
+
+        into_secret(plaintext_bytes)
+    }
+
+    fn decrypt_envelope(&self, bytes: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        let header = EnvelopeHeader::decode(bytes)?;
+        let body = &bytes[HEADER_LEN..];
+        if body.len() < NONCE_LEN {
+            return Err(anyhow::anyhow!("Ciphertext too short"));
+        }
+        let (nonce_bytes, ciphertext_bytes) = body.split_at(NONCE_LEN);
+        let nonce = Nonce::from_slice(nonce_bytes);
+        let aad = &bytes[..HEADER_LEN];
+
+        // Reuse the cached key when the envelope was produced by this vault instance.
+        let derived;
+        let cipher = if header.kdf == self.header.kdf && header.salt == self.header.salt {
+            &self.cipher
+        } else {
+            derived = cipher_for(&header.kdf, self.password.expose_secret(), &header.salt)?;
+            &derived
+        };
+
+        cipher.decrypt(nonce, Payload { msg: ciphertext_bytes, aad })
+            .map(Zeroizing::new)
+            .map_err(|e| anyhow::anyhow!("Decryption failure: {}", e))
+    }
+
+    fn decrypt_legacy(&self, bytes: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        if bytes.len() < NONCE_LEN {
             return Err(anyhow::anyhow!("Ciphertext too short"));
         }
 
-        let (nonce_bytes, ciphertext_bytes) = encrypted_bytes.split_at(12);
+        let key_bytes = Zeroizing::new(*blake3::hash(self.password.expose_secret().as_bytes()).as_bytes());
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key_bytes.as_ref()));
+
+        let (nonce_bytes, ciphertext_bytes) = bytes.split_at(NONCE_LEN);
         let nonce = Nonce::from_slice(nonce_bytes);
-        
-        let plaintext_bytes = self.cipher.decrypt(nonce, ciphertext_bytes)
-            .map_err(|e| anyhow::anyhow!("Decryption failure: {}", e))?;
-            
-        String::from_utf8(plaintext_bytes).context("Invalid UTF-8")
+
+        cipher.decrypt(nonce, ciphertext_bytes)
+            .map(Zeroizing::new)
+            .map_err(|e| anyhow::anyhow!("Decryption failure (legacy v0): {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Argon2id parameters cheap enough for unit tests.
+    const TEST_KDF: KdfParams = KdfParams::Argon2id { m_cost_kib: 64, t_cost: 1, p_cost: 1 };
+
+    #[test]
+    fn envelope_round_trip() {
+        let vault = Vault::with_kdf("pw", TEST_KDF).unwrap();
+        let sealed = vault.encrypt("secret").unwrap();
+        assert_eq!(vault.decrypt(&sealed).unwrap().expose_secret(), "secret");
+        assert!(Vault::with_kdf("other", TEST_KDF).unwrap().decrypt(&sealed).is_err());
+    }
+
+    #[test]
+    fn legacy_v0_blob_still_decrypts() {
+        // v0: bare `nonce + ciphertext`, keyed with an unsalted blake3 hash of the password.
+        let key = blake3::hash(b"pw");
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_bytes()));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let mut blob = nonce.to_vec();
+        blob.extend(cipher.encrypt(&nonce, b"legacy secret".as_ref()).unwrap());
+
+        let vault = Vault::with_kdf("pw", TEST_KDF).unwrap();
+        assert_eq!(vault.decrypt(&hex::encode(&blob)).unwrap().expose_secret(), "legacy secret");
+        assert!(Vault::with_kdf("other", TEST_KDF).unwrap().decrypt(&hex::encode(&blob)).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn vault_file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.bin");
+        let mut vault = Vault::open(&path, "pw").unwrap();
+        vault.set_secret("KEY", "value").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(!sidecar_path(&path, "tmp").exists());
+    }
+
+    fn wrong_password(result: Result<Vault>) -> bool {
+        matches!(result.err().and_then(|e| e.downcast::<VaultError>().ok()), Some(VaultError::WrongPassword))
+    }
+
+    #[test]
+    fn rekey_switches_password_and_keeps_kdf() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.bin");
+        let mut vault = Vault::open(&path, "old").unwrap();
+        vault.set_secret("KEY", "value").unwrap();
+
+        vault.rekey("old", "mid", Some(TEST_KDF)).unwrap();
+        vault.rekey("mid", "new", None).unwrap();
+        assert_eq!(VaultFile::read(&path).unwrap().kdf, TEST_KDF);
+        assert!(!sidecar_path(&path, "bak").exists());
+
+        assert!(wrong_password(Vault::open(&path, "old")));
+        assert!(wrong_password(Vault::open(&path, "mid")));
+        let reopened = Vault::open(&path, "new").unwrap();
+        assert_eq!(reopened.get_secret("KEY").unwrap().unwrap().expose_secret(), "value");
+    }
+
+    #[test]
+    fn interrupted_rekey_leaves_vault_intact() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.bin");
+        let mut vault = Vault::open(&path, "old").unwrap();
+        vault.set_secret("KEY", "value").unwrap();
+        let before = fs::read(&path).unwrap();
+
+        // A directory where the temp file goes makes the write fail after the backup.
+        fs::create_dir(sidecar_path(&path, "tmp")).unwrap();
+        assert!(vault.rekey("old", "new", Some(TEST_KDF)).is_err());
+        assert_eq!(fs::read(&path).unwrap(), before);
+        assert!(!sidecar_path(&path, "bak").exists());
+
+        assert!(wrong_password(Vault::open(&path, "new")));
+        let reopened = Vault::open(&path, "old").unwrap();
+        assert_eq!(reopened.get_secret("KEY").unwrap().unwrap().expose_secret(), "value");
+    }
+
+    #[test]
+    fn swapped_entries_fail_authentication() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.bin");
+        let mut vault = Vault::open(&path, "pw").unwrap();
+        vault.set_secret("STARKNET_PRIVATE_KEY", "0x1").unwrap();
+        vault.set_secret("INFLUENCE_API_KEY", "api").unwrap();
+
+        let mut file = VaultFile::read(&path).unwrap();
+        let key = file.entries["STARKNET_PRIVATE_KEY"].clone();
+        let api = file.entries.insert("INFLUENCE_API_KEY".to_string(), key).unwrap();
+        file.entries.insert("STARKNET_PRIVATE_KEY".to_string(), api);
+        file.write_atomic(&path).unwrap();
+
+        let reopened = Vault::open(&path, "pw").unwrap();
+        for name in ["STARKNET_PRIVATE_KEY", "INFLUENCE_API_KEY"] {
+            let err = reopened.get_secret(name).unwrap_err();
+            assert!(matches!(err.downcast_ref::<VaultError>(), Some(VaultError::EntryAuthentication { .. })));
+        }
+    }
+
+    #[test]
+    fn unbound_v1_files_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.bin");
+        Vault::open(&path, "pw").unwrap();
+        let mut file = VaultFile::read(&path).unwrap();
+        file.version = 1;
+        file.write_atomic(&path).unwrap();
+        assert!(Vault::open(&path, "pw").is_err());
+    }
+
+    fn locked_out(result: Result<Vault>) -> Option<u64> {
+        match result.err()?.downcast::<VaultError>().ok()? {
+            VaultError::LockedOut { retry_after_secs, .. } => Some(retry_after_secs),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn wrong_passwords_back_off() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.bin");
+        Vault::open(&path, "pw").unwrap();
+
+        for _ in 0..FREE_ATTEMPTS {
+            assert!(wrong_password(Vault::open(&path, "guess")));
+        }
+        let attempts = FailedAttempts::load(&sidecar_path(&path, "attempts")).unwrap();
+        assert_eq!(attempts.failures, FREE_ATTEMPTS);
+        // Even the right password waits out the delay, which doubles per failure.
+        assert!(matches!(locked_out(Vault::open(&path, "pw")), Some(1..=BACKOFF_BASE_SECS)));
+        let next = FailedAttempts { failures: FREE_ATTEMPTS + 1, last_failure: 0 };
+        assert_eq!(next.backoff_secs(), 2 * attempts.backoff_secs());
+    }
+
+    #[test]
+    fn right_password_resets_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.bin");
+        Vault::open(&path, "pw").unwrap();
+
+        for _ in 0..FREE_ATTEMPTS - 1 {
+            assert!(wrong_password(Vault::open(&path, "guess")));
+        }
+        Vault::open(&path, "pw").unwrap();
+        assert!(!sidecar_path(&path, "attempts").exists());
+        for _ in 0..FREE_ATTEMPTS - 1 {
+            assert!(wrong_password(Vault::open(&path, "guess")));
+        }
+        Vault::open(&path, "pw").unwrap();
+    }
+
+    #[test]
+    fn blob_unlock_backs_off() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path().join("vault.bin"), "pw").unwrap();
+        let blob = vault.export_blob().unwrap();
+        let vault_id = vault.secret_store().unwrap().vault_id.clone();
+
+        for _ in 0..FREE_ATTEMPTS {
+            assert!(wrong_password(Vault::from_blob(&blob, "guess")));
+        }
+        assert!(locked_out(Vault::from_blob(&blob, "pw")).is_some());
+        fs::remove_file(blob_attempts_path(&vault_id)).unwrap();
+        Vault::from_blob(&blob, "pw").unwrap();
+    }
+
+    #[test]
+    fn concurrent_open_is_refused_while_locked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.bin");
+        Vault::open(&path, "pw").unwrap();
+
+        let held = VaultLock::acquire(&path).unwrap();
+        let err = Vault::open(&path, "pw").err().unwrap();
+        assert!(matches!(err.downcast_ref::<VaultError>(), Some(VaultError::Busy)));
+        drop(held);
+        Vault::open(&path, "pw").unwrap();
+    }
+
+    #[test]
+    fn out_of_bound_kdf_params_are_rejected_at_parse() {
+        let too_much = [
+            KdfParams::Argon2id { m_cost_kib: MAX_M_COST_KIB + 1, t_cost: 1, p_cost: 1 },
+            KdfParams::Argon2id { m_cost_kib: 64, t_cost: MAX_T_COST + 1, p_cost: 1 },
+            KdfParams::Argon2id { m_cost_kib: 64, t_cost: 1, p_cost: MAX_P_COST + 1 },
+        ];
+        for kdf in too_much {
+            let header = EnvelopeHeader { kdf, salt: [0; SALT_LEN] }.encode();
+            assert!(EnvelopeHeader::decode(&header).is_err(), "{:?}", kdf);
+
+            let file = serde_json::json!({
+                "magic": "SPRV",
+                "version": FILE_VERSION,
+                "vault_id": "00",
+                "kdf": kdf,
+                "salt": hex::encode([0u8; SALT_LEN]),
+                "entries": {},
+            });
+            assert!(VaultFile::parse(&serde_json::to_vec(&file).unwrap()).is_err(), "{:?}", kdf);
+            assert!(Vault::with_kdf("pw", kdf).is_err(), "{:?}", kdf);
+        }
+
+        let header = EnvelopeHeader { kdf: TEST_KDF, salt: [0; SALT_LEN] }.encode();
+        assert!(EnvelopeHeader::decode(&header).is_ok());
     }
 }
@@ -59,6 +59,14 @@ def test_balance():
         else:
              console.print("[red]⚠️  Low Balance. Gas Buffer Risk.[/red]")
 
+        # Every registry token with an address on this network (SWAY on Sepolia needs SWAY_TOKEN_ADDRESS)
+        for symbol, _, _ in client.get_known_tokens():
+            try:
+                token = client.get_token_balance(symbol, wallet)
+                console.print(f"   🪙 {token.symbol}: [bold]{token.formatted}[/bold] [dim]({token.amount} base units)[/dim]")
+            except RuntimeError as e:
+                console.print(f"   ⚠️  {symbol}: [yellow]{e}[/yellow]")
+
     except Exception as e:
         console.print(f"[bold red]❌ Failed:[/bold red] {e}")
         sys.exit(1)
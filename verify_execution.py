@@ -0,0 +1,81 @@
+import sys
+import os
+import tempfile
+from rich.console import Console
+from rich.panel import Panel
+
+try:
+    import stark_pyrust_chain
+except ImportError as e:
+    print(f"❌ Import failed: {e}")
+    sys.exit(1)
+
+console = Console()
+
+STRK_TOKEN = "0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d"
+
+# Robust Env Loader for Windows/UTF-8 issues
+def load_env_manual():
+    env_path = ".env"
+    if not os.path.exists(env_path):
+        return
+    try:
+        with open(env_path, "r", encoding="utf-8", errors="ignore") as f:
+            for line in f:
+                line = line.strip()
+                if not line or line.startswith("#"):
+                    continue
+                if "=" in line:
+                    key, val = line.split("=", 1)
+                    if key.strip() not in os.environ:
+                        os.environ[key.strip()] = val.strip()
+    except Exception as e:
+        print(f"Warning: Failed to load .env manually: {e}")
+
+def test_execution():
+    """
+    Submit a signed INVOKE v3 against a local devnet (e.g. `starknet-devnet --seed 0`),
+    using one of its predeployed accounts: a 1 fri STRK transfer to itself.
+    """
+    console.print(Panel.fit("[bold blue]🚀 Signed Invoke Check (Devnet)[/bold blue]"))
+    load_env_manual()
+
+    rpc_url = os.getenv("DEVNET_RPC_URL", "http://127.0.0.1:5050/rpc")
+    account = os.getenv("DEVNET_ACCOUNT_ADDRESS")
+    private_key = os.getenv("DEVNET_PRIVATE_KEY")
+    if not account or not private_key:
+        console.print("[yellow]⚠️  Set DEVNET_ACCOUNT_ADDRESS and DEVNET_PRIVATE_KEY (a predeployed devnet account). Skipping.[/yellow]")
+        return
+
+    try:
+        client = stark_pyrust_chain.PyStarknetClient(rpc_url)
+        with tempfile.TemporaryDirectory() as tmp:
+            vault = stark_pyrust_chain.PyVault.open(os.path.join(tmp, "vault.json"), "devnet-only")
+            vault.set_secret("STARKNET_PRIVATE_KEY", private_key)
+
+            calls = [(STRK_TOKEN, "transfer", [account, "0x1", "0x0"])]
+            simulation = client.simulate(account, calls)
+            console.print(f"   ⛽ Simulated fee: {simulation.overall_fee} {simulation.unit} "
+                          f"[dim](L1 gas {simulation.l1_gas_consumed}, L1 data gas {simulation.l1_data_gas_consumed}, "
+                          f"L2 gas {simulation.l2_gas_consumed})[/dim]")
+            if simulation.revert_reason:
+                console.print(f"[bold red]❌ Simulation reverted:[/bold red] {simulation.revert_reason}")
+                sys.exit(1)
+
+            tx_hash = client.execute(account, calls, vault, "STARKNET_PRIVATE_KEY")
+            console.print(f"   ✅ Submitted: [bold green]{tx_hash}[/bold green]")
+
+            receipt = client.wait_for_transaction(tx_hash, timeout_secs=60, poll_interval_secs=1)
+            if not receipt.succeeded:
+                console.print(f"[bold red]❌ {receipt.status}:[/bold red] {receipt.revert_reason}")
+                sys.exit(1)
+            console.print(f"   ✅ {receipt.status} in block {receipt.block_number}, "
+                          f"paid {receipt.actual_fee} {receipt.fee_unit}")
+            console.print(f"   🔢 Nonce after submit: {client.get_nonce(account)}")
+
+    except Exception as e:
+        console.print(f"[bold red]❌ Failed:[/bold red] {e}")
+        sys.exit(1)
+
+if __name__ == "__main__":
+    test_execution()
@@ -43,6 +43,22 @@ def verify_logistics():
         try:
             nonce = client.get_nonce(wallet)
             console.print(f"   ✅ Nonce: [bold green]{nonce}[/bold green] (Verified from Mainnet)")
+
+            _, pending, gaps = client.get_nonce_report(wallet)
+            console.print(f"   🔒 Locally reserved (not landed): {[n for n, _ in pending] or 'none'}")
+            if gaps:
+                console.print(f"   ⚠️  Nonce gaps: [yellow]{gaps}[/yellow] (the next execute fills them)")
+
+            try:
+                quorum = client.get_nonce_quorum(wallet, 2)
+                console.print(f"   🗳️  Quorum nonce: [bold green]{quorum.value}[/bold green] at block {quorum.block_number} "
+                              f"({len(quorum.providers)} providers agree)")
+                if quorum.lagging:
+                    console.print(f"   ⚠️  Lagging providers: [yellow]{quorum.lagging}[/yellow]")
+            except stark_pyrust_chain.QuorumDisagreementError as e:
+                console.print(f"   ❌ [bold red]Providers disagree on the nonce:[/bold red] {e.answers}")
+            except RuntimeError as e:
+                console.print(f"   ⚠️  Quorum nonce skipped: [yellow]{e}[/yellow]")
         except Exception as e:
             if "ContractNotFound" in str(e) or "not found" in str(e).lower():
                  console.print(f"   ⚠️  Nonce Fetch: [yellow]Account not deployed (ContractNotFound)[/yellow]. Defaulting to 0.")